@@ -0,0 +1,340 @@
+use std::collections::VecDeque;
+
+use crate::snake::{Direction, SnakePart};
+
+use crate::tr;
+use crate::ui::{
+  dimensions::Pos,
+  ui_items::Symbol
+};
+
+use crate::world::World;
+
+const DIRECTIONS: [Direction; 4] = [
+  Direction::Up, Direction::Down, Direction::Left, Direction::Right
+];
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Difficulty {
+  Greedy, Cautious, Aggressive
+}
+
+impl Difficulty {
+  pub fn name(&self) -> &'static str {
+    match self {
+      Difficulty::Greedy     => tr!("bot.greedy"),
+      Difficulty::Cautious   => tr!("bot.cautious"),
+      Difficulty::Aggressive => tr!("bot.aggressive")
+    }
+  }
+
+  pub fn key(&self) -> &'static str {
+    match self {
+      Difficulty::Greedy     => "greedy",
+      Difficulty::Cautious   => "cautious",
+      Difficulty::Aggressive => "aggressive"
+    }
+  }
+}
+
+impl TryFrom<&str> for Difficulty {
+  type Error = String;
+
+  fn try_from(name: &str) -> std::result::Result<Self, Self::Error> {
+    match name {
+      "greedy"     => Ok(Difficulty::Greedy),
+      "cautious"   => Ok(Difficulty::Cautious),
+      "aggressive" => Ok(Difficulty::Aggressive),
+      _ => Err(name.to_string())
+    }
+  }
+}
+
+struct Search {
+  dist: Vec<u32>,
+  first: Vec<Option<Direction>>,
+  prev: Vec<Option<Pos>>
+}
+
+struct Planner<'a> {
+  world: &'a World,
+  blocked: Vec<bool>
+}
+
+impl<'a> Planner<'a> {
+  fn new(world: &'a World, player: usize, difficulty: Difficulty) -> Self {
+    let size = world.field_size;
+    let mut planner = Planner {
+      world,
+      blocked: vec![false; size.width as usize * size.height as usize]
+    };
+
+    for brick in &world.field.bricks {
+      planner.block(&brick.get_pos());
+    }
+
+    for hazard in &world.field.hazards {
+      planner.block(&hazard.get_pos());
+
+      if difficulty == Difficulty::Cautious {
+        for dir in DIRECTIONS {
+          planner.block(&dir.next_pos(hazard.get_pos(), size));
+        }
+      }
+    }
+
+    for (i, other) in world.players.iter().enumerate() {
+      if !other.is_alive() {
+        continue;
+      }
+
+      let parts = other.snake.get_parts();
+      for part in &parts[..parts.len() - 1] {
+        planner.block(&part.get_pos());
+      }
+
+      if i != player && difficulty == Difficulty::Cautious {
+        for dir in DIRECTIONS {
+          planner.block(&dir.next_pos(other.snake.get_head_pos(), size));
+        }
+      }
+    }
+
+    planner
+  }
+
+  fn index(&self, pos: &Pos) -> usize {
+    (pos.y - 1) as usize * self.world.field_size.width as usize +
+    (pos.x - 2) as usize
+  }
+
+  fn block(&mut self, pos: &Pos) {
+    let index = self.index(pos);
+    if let Some(cell) = self.blocked.get_mut(index) {
+      *cell = true;
+    }
+  }
+
+  fn is_free(&self, pos: &Pos) -> bool {
+    !self.blocked.get(self.index(pos)).copied().unwrap_or(true)
+  }
+
+  fn step(&self, pos: Pos, dir: Direction) -> Pos {
+    let size = self.world.field_size;
+    let mut part = SnakePart::new(Symbol::new(pos));
+    part.update(dir, size);
+    part.enter_portal(dir, size, &self.world.field.portals);
+    part.get_pos()
+  }
+
+  fn moves(&self, pos: Pos, heading: Direction)
+      -> impl Iterator<Item = (Direction, Pos)> + '_ {
+
+    DIRECTIONS.into_iter()
+      .filter(move |dir| !heading.is_opposite(dir))
+      .map(move |dir| (dir, self.step(pos, dir)))
+      .filter(|(_, next)| self.is_free(next))
+  }
+
+  fn search(&self, start: Pos, heading: Direction) -> Search {
+    let cells = self.blocked.len();
+    let mut search = Search {
+      dist: vec![u32::MAX; cells],
+      first: vec![None; cells],
+      prev: vec![None; cells]
+    };
+    let mut queue = VecDeque::new();
+
+    for (dir, next) in self.moves(start, heading) {
+      let index = self.index(&next);
+      if search.dist[index] == u32::MAX {
+        search.dist[index] = 1;
+        search.first[index] = Some(dir);
+        search.prev[index] = Some(start);
+        queue.push_back((next, dir));
+      }
+    }
+
+    while let Some((pos, dir)) = queue.pop_front() {
+      let index = self.index(&pos);
+
+      for (next_dir, next) in self.moves(pos, dir) {
+        let next_index = self.index(&next);
+        if search.dist[next_index] == u32::MAX {
+          search.dist[next_index] = search.dist[index] + 1;
+          search.first[next_index] = search.first[index];
+          search.prev[next_index] = Some(pos);
+          queue.push_back((next, next_dir));
+        }
+      }
+    }
+
+    search
+  }
+
+  fn area(&self, start: Pos, limit: usize) -> usize {
+    let mut seen = vec![false; self.blocked.len()];
+    let mut queue = VecDeque::from([start]);
+    let mut count = 0;
+    seen[self.index(&start)] = true;
+
+    while let Some(pos) = queue.pop_front() {
+      count += 1;
+      if count >= limit {
+        break;
+      }
+
+      for dir in DIRECTIONS {
+        let next = self.step(pos, dir);
+        let index = self.index(&next);
+        if self.is_free(&next) && !seen[index] {
+          seen[index] = true;
+          queue.push_back(next);
+        }
+      }
+    }
+
+    count
+  }
+
+  fn roomiest(&self, head: Pos, heading: Direction) -> Option<Direction> {
+    let limit = self.blocked.len();
+    self.moves(head, heading)
+      .max_by_key(|(_, next)| self.area(*next, limit))
+      .map(|(dir, _)| dir)
+  }
+
+  fn intercept(&self, player: usize, own: &Search) -> Option<Pos> {
+    let apple = self.world.field.apple.get_pos();
+    let own_dist = own.dist[self.index(&apple)];
+
+    for (i, other) in self.world.players.iter().enumerate() {
+      if i == player || !other.is_alive() {
+        continue;
+      }
+
+      let theirs = self.search(
+        other.snake.get_head_pos(), other.snake.get_direction()
+      );
+      if theirs.dist[self.index(&apple)] >= own_dist {
+        continue;
+      }
+
+      let mut path = vec![apple];
+      while let Some(prev) = theirs.prev[self.index(path.last().unwrap())] {
+        if prev == other.snake.get_head_pos() {
+          break;
+        }
+        path.push(prev);
+      }
+
+      if let Some(cut) = path.iter().rev().find(|pos| {
+        let index = self.index(pos);
+        own.dist[index] <= theirs.dist[index]
+      }) {
+        return Some(*cut);
+      }
+    }
+
+    None
+  }
+}
+
+pub fn choose_direction(world: &World, player: usize,
+    difficulty: Difficulty) -> Direction {
+
+  let snake = &world.players[player].snake;
+  let (head, heading) = (snake.get_head_pos(), snake.get_direction());
+  let planner = Planner::new(world, player, difficulty);
+  let search = planner.search(head, heading);
+
+  let mut target = world.field.apple.get_pos();
+  if difficulty == Difficulty::Aggressive {
+    target = planner.intercept(player, &search).unwrap_or(target);
+  }
+
+  let mut choice = search.first[planner.index(&target)];
+
+  if difficulty == Difficulty::Cautious {
+    let needed = snake.get_parts().len() + 1;
+    choice = choice.filter(|dir|
+      planner.area(planner.step(head, *dir), needed) >= needed
+    );
+  }
+
+  choice
+    .or_else(|| planner.roomiest(head, heading))
+    .unwrap_or(heading)
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::food::FoodType;
+  use crate::snake::Direction;
+  use crate::testing::{empty_world, place, put_apple, put_brick};
+  use crate::ui::dimensions::Pos;
+  use crate::world::World;
+  use super::{Difficulty, choose_direction};
+
+  fn world(bots: Vec<Difficulty>) -> World {
+    empty_world((20, 10), 0, bots)
+  }
+
+  #[test]
+  fn test_difficulty_from_str() {
+    assert_eq!(Difficulty::try_from("greedy"), Ok(Difficulty::Greedy));
+    assert_eq!(Difficulty::try_from("aggressive"), Ok(Difficulty::Aggressive));
+    assert!(Difficulty::try_from("smart").is_err());
+  }
+
+  #[test]
+  fn test_heads_for_apple_around_bricks() {
+    let mut world = world(vec![Difficulty::Greedy]);
+    place(&mut world, 0, Direction::Right, Pos::from((5, 5)));
+    put_apple(&mut world, FoodType::GreenApple, Pos::from((9, 5)));
+    put_brick(&mut world, Pos::from((6, 5)));
+
+    let dir = choose_direction(&world, 0, Difficulty::Greedy);
+    assert!(dir == Direction::Up || dir == Direction::Down);
+
+    for _ in 0..200 {
+      world.tick();
+      if world.players[0].score.total() > 0 {
+        break;
+      }
+    }
+    assert!(world.players[0].is_alive());
+    assert!(world.players[0].score.total() > 0);
+  }
+
+  #[test]
+  fn test_avoids_dead_end() {
+    let mut world = world(vec![Difficulty::Cautious]);
+    place(&mut world, 0, Direction::Up, Pos::from((5, 5)));
+    for pos in [(4, 4), (4, 3), (6, 4), (6, 3), (5, 2)] {
+      put_brick(&mut world, Pos::from(pos));
+    }
+    put_apple(&mut world, FoodType::GreenApple, Pos::from((5, 3)));
+    world.players[0].snake.add_part();
+    world.players[0].snake.add_part();
+
+    let dir = choose_direction(&world, 0, Difficulty::Cautious);
+    assert!(dir != Direction::Up);
+    assert!(choose_direction(&world, 0, Difficulty::Greedy) == Direction::Up);
+  }
+
+  #[test]
+  fn test_bots_play_on_crowded_field() {
+    let mut world = world(vec![
+      Difficulty::Greedy, Difficulty::Cautious, Difficulty::Aggressive
+    ]);
+
+    for _ in 0..2000 {
+      world.tick();
+      if world.is_over() {
+        break;
+      }
+    }
+    assert!(world.players.iter().map(|p| p.score.total()).sum::<u64>() > 0);
+  }
+}
@@ -0,0 +1,293 @@
+use std::{
+  sync::{
+    Arc, Mutex,
+    atomic::{AtomicU64, Ordering}
+  },
+  thread,
+  time::Duration
+};
+
+use crate::ai::Difficulty;
+use crate::bot::{BotProcess, MAX_TICKS};
+use crate::error;
+use crate::json::{Object, ToJson};
+use crate::tr;
+use crate::ui::dimensions::Size;
+use crate::world::{World, GameOptions, TICK_MS};
+
+#[derive(Clone)]
+pub enum Pilot {
+  Builtin(Difficulty),
+  External { cmd: String, timeout: Duration }
+}
+
+impl From<&str> for Pilot {
+  fn from(name: &str) -> Self {
+    match Difficulty::try_from(name) {
+      Ok(difficulty) => Pilot::Builtin(difficulty),
+      Err(_) => Pilot::External {
+        cmd: name.to_string(),
+        timeout: Duration::from_millis(100)
+      }
+    }
+  }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct GameResult {
+  pub seed: u64,
+  pub score: u64,
+  pub length: usize,
+  pub ticks: u64,
+  pub death: Option<&'static str>
+}
+
+impl GameResult {
+  pub fn from_world(world: &World, player: usize) -> Self {
+    let player = &world.players[player];
+
+    Self {
+      seed: world.seed,
+      score: player.score.total(),
+      length: player.snake.get_parts().len(),
+      ticks: world.tick,
+      death: player.death.map(|cause| cause.key())
+    }
+  }
+
+  pub fn get_time(&self) -> f64 {
+    self.ticks as f64 * TICK_MS as f64 / 1000.0
+  }
+}
+
+impl ToJson for GameResult {
+  fn to_json(&self) -> String {
+    Object::new()
+      .field("seed", &self.seed)
+      .field("score", &self.score)
+      .field("length", &self.length)
+      .field("ticks", &self.ticks)
+      .field("time", &self.get_time())
+      .field("death", &self.death)
+      .to_json()
+  }
+}
+
+pub fn play(pilot: &Pilot, field_size: Size,
+    options: &GameOptions, seed: u64) -> error::Result<GameResult> {
+
+  let mut options = GameOptions { seed: Some(seed), ..options.clone() };
+
+  match pilot {
+    Pilot::Builtin(difficulty) => {
+      options.players = 0;
+      options.bots.insert(0, *difficulty);
+
+      let mut world = World::new(field_size, &options);
+      while !world.is_over() && world.tick < MAX_TICKS {
+        world.tick();
+      }
+      Ok(GameResult::from_world(&world, 0))
+    },
+    Pilot::External { cmd, timeout } => {
+      options.players = 1;
+
+      let mut bot = BotProcess::spawn(cmd, *timeout, true)?;
+      let mut world = World::new(field_size, &options);
+      bot.play(&mut world);
+      Ok(GameResult::from_world(&world, 0))
+    }
+  }
+}
+
+impl Pilot {
+  pub fn bench(&self, games: u64, seed_start: u64, jobs: usize,
+      field_size: Size, options: &GameOptions) -> error::Result<Vec<GameResult>> {
+
+    let next = Arc::new(AtomicU64::new(0));
+    let results = Arc::new(Mutex::new(Vec::new()));
+    let jobs = jobs.clamp(1, games.max(1) as usize);
+
+    let workers: Vec<_> = (0..jobs)
+      .map(|_| {
+        let (next, results) = (next.clone(), results.clone());
+        let (pilot, options) = (self.clone(), options.clone());
+
+        thread::spawn(move || -> error::Result<()> {
+          loop {
+            let game = next.fetch_add(1, Ordering::Relaxed);
+            if game >= games {
+              return Ok(());
+            }
+
+            let result = play(&pilot, field_size, &options, seed_start + game)?;
+            results.lock().unwrap().push(result);
+          }
+        })
+      })
+      .collect();
+
+    for worker in workers {
+      worker.join().unwrap()?;
+    }
+
+    let mut results = results.lock().unwrap().clone();
+    results.sort_by_key(|result| result.seed);
+    Ok(results)
+  }
+}
+
+pub struct Summary {
+  pub games: usize,
+  pub mean_score: f64,
+  pub median_score: f64,
+  pub max_score: u64,
+  pub mean_length: f64,
+  pub mean_time: f64,
+  pub deaths: Vec<(&'static str, usize)>
+}
+
+impl Summary {
+  pub fn new(results: &[GameResult]) -> Self {
+    let games = results.len();
+    let count = games.max(1) as f64;
+
+    let mut scores: Vec<u64> = results.iter().map(|r| r.score).collect();
+    scores.sort_unstable();
+    let median_score = match games {
+      0 => 0.0,
+      n if n % 2 == 1 => scores[n / 2] as f64,
+      n => (scores[n / 2 - 1] + scores[n / 2]) as f64 / 2.0
+    };
+
+    let mut deaths: Vec<(&'static str, usize)> = Vec::new();
+    for result in results {
+      let cause = result.death.unwrap_or("survived");
+      match deaths.iter_mut().find(|(name, _)| *name == cause) {
+        Some((_, n)) => *n += 1,
+        None => deaths.push((cause, 1))
+      }
+    }
+    deaths.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+
+    Self {
+      games,
+      mean_score: scores.iter().sum::<u64>() as f64 / count,
+      median_score,
+      max_score: scores.last().copied().unwrap_or(0),
+      mean_length: results.iter().map(|r| r.length).sum::<usize>() as f64 / count,
+      mean_time: results.iter().fold(0.0, |t, r| t + r.get_time()) / count,
+      deaths
+    }
+  }
+
+  pub fn to_json(&self, results: &[GameResult]) -> String {
+    let mut deaths = Object::new();
+    for (cause, count) in &self.deaths {
+      deaths = deaths.field(cause, count);
+    }
+
+    Object::new()
+      .field("games", &self.games)
+      .field("mean_score", &self.mean_score)
+      .field("median_score", &self.median_score)
+      .field("max_score", &self.max_score)
+      .field("mean_length", &self.mean_length)
+      .field("mean_time", &self.mean_time)
+      .field("deaths", &deaths)
+      .field("results", results)
+      .to_json()
+  }
+
+  pub fn table(&self) -> String {
+    if self.games == 0 {
+      return tr!("bench.no_games").to_string();
+    }
+
+    let mut rows = vec![
+      (tr!("bench.games").to_string(), self.games.to_string()),
+      (tr!("bench.mean_score").to_string(), format!("{:.1}", self.mean_score)),
+      (tr!("bench.median_score").to_string(), format!("{:.1}", self.median_score)),
+      (tr!("bench.max_score").to_string(), self.max_score.to_string()),
+      (tr!("bench.mean_length").to_string(), format!("{:.1}", self.mean_length)),
+      (tr!("bench.mean_time").to_string(), format!("{:.1}", self.mean_time))
+    ];
+
+    for (cause, count) in &self.deaths {
+      let key = format!("bench.death_{}", cause);
+      rows.push((
+        tr!(&key).to_string(),
+        format!("{} ({:.1}%)", count,
+          *count as f64 * 100.0 / self.games as f64)
+      ));
+    }
+
+    let width = rows.iter()
+      .map(|(name, _)| name.chars().count())
+      .max()
+      .unwrap_or(0);
+
+    rows.iter()
+      .map(|(name, value)| format!("{: <2$}  {}", name, value, width))
+      .collect::<Vec<_>>()
+      .join("\n")
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::ai::Difficulty;
+  use crate::level::Level;
+  use crate::ui::dimensions::Size;
+  use crate::world::GameOptions;
+  use super::{GameResult, Pilot, Summary};
+
+  fn result(seed: u64, score: u64, death: Option<&'static str>) -> GameResult {
+    GameResult { seed, score, length: 3, ticks: 200, death }
+  }
+
+  #[test]
+  fn test_summary() {
+    let results = vec![
+      result(0, 10, Some("brick")),
+      result(1, 40, Some("self")),
+      result(2, 30, Some("brick")),
+      result(3, 20, None)
+    ];
+    let summary = Summary::new(&results);
+
+    assert_eq!(summary.games, 4);
+    assert_eq!(summary.mean_score, 25.0);
+    assert_eq!(summary.median_score, 25.0);
+    assert_eq!(summary.max_score, 40);
+    assert_eq!(summary.mean_time, 10.0);
+    assert_eq!(summary.deaths[0], ("brick", 2));
+    let table = summary.table();
+    assert!(table.lines().any(|line|
+      line.starts_with("Гибель: кирпич ") && line.ends_with(" 2 (50.0%)")));
+    assert!(table.contains("Выжил"));
+    assert!(summary.to_json(&results).contains(r#""deaths":{"brick":2,"#));
+  }
+
+  #[test]
+  fn test_pilot_from_name() {
+    assert!(matches!(Pilot::from("greedy"), Pilot::Builtin(Difficulty::Greedy)));
+    assert!(matches!(Pilot::from("./my_bot"), Pilot::External { .. }));
+  }
+
+  #[test]
+  fn test_run_is_reproducible() {
+    let options = GameOptions {
+      level: Some(Level::parse("brick 5 5\nbrick 6 6").unwrap()),
+      ..GameOptions::default()
+    };
+    let pilot = Pilot::Builtin(Difficulty::Greedy);
+    let size = Size::from((12, 8));
+
+    let parallel = pilot.bench(6, 100, 3, size, &options).unwrap();
+    let serial = pilot.bench(6, 100, 1, size, &options).unwrap();
+    assert_eq!(parallel, serial);
+    assert_eq!(parallel.iter().map(|r| r.seed).collect::<Vec<_>>(),
+      (100..106).collect::<Vec<_>>());
+  }
+}
@@ -0,0 +1,285 @@
+use std::{
+  io::{self, BufRead, BufReader, Write},
+  process::{Child, ChildStdin, Command, Stdio},
+  sync::mpsc::{self, Receiver},
+  thread,
+  time::Duration
+};
+
+use crate::error::{self, SnakeError};
+use crate::food::FoodType;
+use crate::hazard::HazardKind;
+use crate::json::{Object, ToJson};
+use crate::snake::Direction;
+use crate::tr;
+use crate::ui::{
+  UI, cast,
+  dimensions::Pos,
+  renderer::Renderer
+};
+use crate::world::{World, Player, TICK_MS};
+
+pub const MAX_TICKS: u64 = 100_000;
+
+pub struct BotProcess {
+  child: Child,
+  stdin: ChildStdin,
+  replies: Receiver<String>,
+  timeout: Duration
+}
+
+impl BotProcess {
+  pub fn spawn(cmd: &str, timeout: Duration,
+      quiet: bool) -> error::Result<Self> {
+
+    if cmd.trim().is_empty() {
+      return Err(SnakeError::Args(tr!("bot.empty_command").to_string()));
+    }
+
+    let (shell, flag) = if cfg!(windows) { ("cmd", "/C") } else { ("sh", "-c") };
+    let mut child = Command::new(shell)
+      .args([flag, cmd])
+      .stdin(Stdio::piped())
+      .stdout(Stdio::piped())
+      .stderr(if quiet { Stdio::null() } else { Stdio::inherit() })
+      .spawn()?;
+
+    let stdin = child.stdin.take().unwrap();
+    let stdout = child.stdout.take().unwrap();
+    let (sender, replies) = mpsc::channel();
+
+    thread::spawn(move || {
+      for line in BufReader::new(stdout).lines() {
+        let Ok(line) = line else { break };
+        if sender.send(line).is_err() {
+          break;
+        }
+      }
+    });
+
+    Ok(Self { child, stdin, replies, timeout })
+  }
+
+  pub fn ask(&mut self, state: &str) -> Option<Direction> {
+    while self.replies.try_recv().is_ok() {}
+
+    writeln!(self.stdin, "{}", state).ok()?;
+    self.stdin.flush().ok()?;
+
+    let reply = self.replies.recv_timeout(self.timeout).ok()?;
+    parse_reply(&reply)
+  }
+
+  pub fn request(world: &World, player: usize) -> Option<String> {
+    (world.players[player].is_alive() && world.will_move(player))
+      .then(|| state_json(world, player))
+  }
+
+  pub fn steer(&mut self, world: &mut World, player: usize) {
+    let reply = Self::request(world, player).and_then(|state| self.ask(&state));
+    if let Some(dir) = reply {
+      world.players[player].push_direction(dir);
+    }
+  }
+
+  pub fn finish(&mut self, world: &World, player: usize) {
+    let _ = writeln!(self.stdin, "{}", state_json(world, player));
+    let _ = self.stdin.flush();
+  }
+
+  pub fn play(&mut self, world: &mut World) {
+    while !world.is_over() && world.tick < MAX_TICKS {
+      self.steer(world, 0);
+      world.tick();
+    }
+    self.finish(world, 0);
+  }
+
+  pub fn play_recorded(&mut self, world: &mut World,
+      ui: &mut UI) -> io::Result<()> {
+
+    ui.set_players(&[world.players[0].snake.get_head_color()]);
+    ui.init(world)?;
+    while !world.is_over() && world.tick < MAX_TICKS {
+      self.steer(world, 0);
+      let events = world.tick();
+      ui.render(world, &events)?;
+      ui.print_time(&(world.tick as f64 * TICK_MS as f64 / 1000.0))?;
+      cast::advance(Duration::from_millis(TICK_MS));
+    }
+    self.finish(world, 0);
+
+    let player = &world.players[0];
+    let rows: Vec<_> = player.score.breakdown().into_iter()
+      .map(|(name, value)| (name.to_string(), value))
+      .collect();
+    ui.print_popup_message(player.death.map_or("", |cause| cause.message()))?;
+    ui.print_game_over(&rows, false)
+  }
+}
+
+impl Drop for BotProcess {
+  fn drop(&mut self) {
+    let _ = self.child.kill();
+    let _ = self.child.wait();
+  }
+}
+
+fn field_pos(pos: Pos) -> Pos {
+  Pos::from((pos.x - 2, pos.y - 1))
+}
+
+fn dir_name(dir: Direction) -> &'static str {
+  match dir {
+    Direction::Up    => "up",
+    Direction::Down  => "down",
+    Direction::Left  => "left",
+    Direction::Right => "right"
+  }
+}
+
+fn food_name(food_type: FoodType) -> &'static str {
+  match food_type {
+    FoodType::GreenApple => "green",
+    FoodType::GoldApple  => "gold",
+    FoodType::Brick      => "brick"
+  }
+}
+
+fn hazard_name(kind: HazardKind) -> &'static str {
+  match kind {
+    HazardKind::Patrol   => "patrol",
+    HazardKind::Creature => "creature",
+    HazardKind::Rock     => "rock"
+  }
+}
+
+pub fn state_json(world: &World, player: usize) -> String {
+  let me = &world.players[player];
+  let field = &world.field;
+  let body = |p: &Player| -> Vec<Pos> {
+    p.snake.get_parts().iter().map(|part| field_pos(part.get_pos())).collect()
+  };
+
+  let food = Object::new()
+    .field("type", food_name(field.apple.get_type()))
+    .field("value", &field.apple.get_value())
+    .field("pos", &field_pos(field.apple.get_pos()));
+
+  let hazards: Vec<Object> = field.hazards.iter()
+    .map(|hazard| Object::new()
+      .field("type", hazard_name(hazard.get_kind()))
+      .field("pos", &field_pos(hazard.get_pos())))
+    .collect();
+
+  let portals: Vec<Vec<Pos>> = field.portals.iter()
+    .map(|portal| portal.get_ends().into_iter().map(field_pos).collect())
+    .collect();
+
+  let opponents: Vec<Vec<Pos>> = world.players.iter().enumerate()
+    .filter(|(i, p)| *i != player && p.is_alive())
+    .map(|(_, p)| body(p))
+    .collect();
+
+  Object::new()
+    .field("tick", &world.tick)
+    .field("field", &Object::new()
+      .field("width", &world.field_size.width)
+      .field("height", &world.field_size.height))
+    .field("alive", &me.is_alive())
+    .field("score", &me.score.total())
+    .field("direction", dir_name(me.snake.get_direction()))
+    .field("boost", &me.boost)
+    .field("snake", &body(me))
+    .field("food", &food)
+    .field("bricks", &field.bricks.iter()
+      .map(|brick| field_pos(brick.get_pos()))
+      .collect::<Vec<_>>())
+    .field("hazards", &hazards)
+    .field("portals", &portals)
+    .field("opponents", &opponents)
+    .to_json()
+}
+
+pub fn parse_reply(line: &str) -> Option<Direction> {
+  let word = line.trim().trim_matches('"').to_ascii_lowercase();
+
+  match word.as_str() {
+    "up"    => Some(Direction::Up),
+    "down"  => Some(Direction::Down),
+    "left"  => Some(Direction::Left),
+    "right" => Some(Direction::Right),
+    _ if word.chars().count() == 1 =>
+      Direction::try_from(word.chars().next()?).ok(),
+    _ => None
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::time::Duration;
+
+  use crate::food::FoodType;
+  use crate::snake::Direction;
+  use crate::testing::{empty_world, place, put_apple, put_brick};
+  use crate::ui::dimensions::Pos;
+  use crate::world::World;
+  use super::{BotProcess, parse_reply, state_json};
+
+  fn world() -> World {
+    let mut world = empty_world((20, 10), 1, Vec::new());
+    place(&mut world, 0, Direction::Right, Pos::from((5, 5)));
+    put_apple(&mut world, FoodType::GoldApple, Pos::from((9, 5)));
+    put_brick(&mut world, Pos::from((2, 1)));
+    world
+  }
+
+  #[test]
+  fn test_parse_reply() {
+    assert!(parse_reply("up\n") == Some(Direction::Up));
+    assert!(parse_reply("\"LEFT\"") == Some(Direction::Left));
+    assert!(parse_reply("r") == Some(Direction::Right));
+    assert!(parse_reply("sideways").is_none());
+    assert!(parse_reply("").is_none());
+  }
+
+  #[test]
+  fn test_state_json() {
+    let state = state_json(&world(), 0);
+    assert!(state.contains(r#""field":{"width":20,"height":10}"#));
+    assert!(state.contains(r#""snake":[[3,4]]"#));
+    assert!(state.contains(r#""food":{"type":"gold","value":20,"pos":[7,4]}"#));
+    assert!(state.contains(r#""bricks":[[0,0]]"#));
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn test_bot_process_replies() {
+    let mut bot = BotProcess::spawn(
+      "while read line; do echo down; done",
+      Duration::from_secs(5), true
+    ).unwrap();
+    let mut world = world();
+
+    for _ in 0..4 {
+      bot.steer(&mut world, 0);
+      world.tick();
+    }
+    assert!(world.players[0].snake.get_head_pos() == Pos::from((5, 6)));
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn test_bot_timeout_keeps_going() {
+    let mut bot = BotProcess::spawn(
+      "read line; echo sideways; sleep 5", Duration::from_millis(10), true
+    ).unwrap();
+    let mut world = world();
+
+    for _ in 0..8 {
+      bot.steer(&mut world, 0);
+      world.tick();
+    }
+    assert!(world.players[0].snake.get_head_pos() == Pos::from((7, 5)));
+  }
+}
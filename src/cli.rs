@@ -0,0 +1,353 @@
+use std::{path::PathBuf, thread, time::Duration};
+
+use snake::ai::Difficulty;
+use snake::{Pilot, Address, DEFAULT_PORT};
+use snake::error::{self, SnakeError};
+use snake::level::Level;
+use snake::stats::Format;
+use snake::tr;
+use snake::ui::dimensions::Size;
+use snake::world::{GameOptions, MAX_PLAYERS, MIN_FIELD_SIZE};
+
+pub enum Command {
+  Menu,
+  Play(GameOptions, Option<Address>),
+  Resume(Option<Address>),
+  Stats(Format),
+  Serve { bind: String, port: u16, field_size: Size, options: GameOptions },
+  Join(String),
+  Watch(Address),
+  Env {
+    episodes: u64,
+    seed: u64,
+    field_size: Size,
+    options: GameOptions
+  },
+  Bench {
+    pilot: Pilot,
+    games: u64,
+    seed_start: u64,
+    jobs: usize,
+    json: bool,
+    field_size: Size,
+    options: GameOptions
+  },
+  Bot {
+    cmd: String,
+    timeout: Duration,
+    headless: bool,
+    field_size: Size,
+    options: GameOptions
+  }
+}
+
+impl Command {
+  pub fn renders(&self) -> bool {
+    !matches!(self, Command::Stats(_) | Command::Serve { .. } |
+      Command::Bench { .. } | Command::Env { .. })
+  }
+}
+
+fn value<I>(args: &mut I, name: &str) -> error::Result<String>
+where I: Iterator<Item = String>, {
+  args.next().ok_or(SnakeError::Args(tr!("args.needs_value", name)))
+}
+
+fn number<T: std::str::FromStr, I>(args: &mut I, name: &str) -> error::Result<T>
+where I: Iterator<Item = String>, {
+  value(args, name)?.parse()
+    .map_err(|_| SnakeError::Args(tr!("args.needs_number", name)))
+}
+
+pub fn take_option(args: &mut Vec<String>,
+    name: &str) -> error::Result<Option<String>> {
+
+  let Some(i) = args.iter().position(|arg| arg == name) else {
+    return Ok(None);
+  };
+
+  args.remove(i);
+  if i < args.len() {
+    Ok(Some(args.remove(i)))
+  }
+  else {
+    Err(SnakeError::Args(tr!("args.needs_value", name)))
+  }
+}
+
+pub fn parse<I>(args: I) -> error::Result<Command>
+where I: IntoIterator<Item = String>, {
+  let mut args = args.into_iter().peekable();
+  if args.peek().is_none() {
+    return Ok(Command::Menu);
+  }
+
+  let command = match args.peek().map(String::as_str) {
+    Some("play") | Some("serve") | Some("join") | Some("bot") | Some("env") |
+    Some("bench") | Some("stats") | Some("watch") =>
+      args.next().unwrap(),
+    _ => "play".to_string()
+  };
+
+  let mut options = GameOptions::default();
+  let mut port = DEFAULT_PORT;
+  let mut bind = "127.0.0.1".to_string();
+  let mut field_size = Size::from((40, 16));
+  let mut address = None;
+  let mut cmd = None;
+  let mut timeout = Duration::from_millis(100);
+  let mut headless = false;
+  let mut episodes = 1000;
+  let mut ai = None;
+  let mut games = 100;
+  let mut seed_start = 0;
+  let mut jobs = thread::available_parallelism().map_or(1, |n| n.get());
+  let mut json = false;
+  let mut resume = false;
+  let mut broadcast = None;
+  let mut format = Format::Table;
+
+  while let Some(arg) = args.next() {
+    match arg.as_str() {
+      "--level" => {
+        let path = PathBuf::from(value(&mut args, "--level")?);
+        options.level = Some(Level::load(&path)?);
+      },
+      "--arcade" => options.arcade = true,
+      "--demo" if command == "play" => options.demo = true,
+      "--resume" if command == "play" => resume = true,
+      "--broadcast" if command == "play" => broadcast =
+        Some(Address::parse(&value(&mut args, "--broadcast")?)?),
+      "--two-players" => options.players = 2,
+      "--bot" => {
+        let name = value(&mut args, "--bot")?;
+        let difficulty = Difficulty::try_from(name.as_str())
+          .map_err(|name| SnakeError::Args(tr!("args.unknown_bot", name)))?;
+        options.bots.push(difficulty);
+      },
+      "--players" if command == "serve" =>
+        options.players = number(&mut args, "--players")?,
+      "--port" if command == "serve" =>
+        port = number(&mut args, "--port")?,
+      "--bind" if command == "serve" => bind = value(&mut args, "--bind")?,
+      "--cmd" if command == "bot" => cmd = Some(value(&mut args, "--cmd")?),
+      "--ai" if command == "bench" => ai = Some(value(&mut args, "--ai")?),
+      "--games" if command == "bench" =>
+        games = number(&mut args, "--games")?,
+      "--seed-start" if command == "bench" =>
+        seed_start = number(&mut args, "--seed-start")?,
+      "--jobs" if command == "bench" => jobs = number(&mut args, "--jobs")?,
+      "--json" if command == "bench" => json = true,
+      "--json" if command == "stats" => format = Format::Json,
+      "--csv" if command == "stats" => format = Format::Csv,
+      "--timeout" if command == "bot" || command == "bench" =>
+        timeout = Duration::from_millis(number(&mut args, "--timeout")?),
+      "--headless" if command == "bot" => headless = true,
+      "--episodes" if command == "env" =>
+        episodes = number(&mut args, "--episodes")?,
+      "--seed" => options.seed = Some(number(&mut args, "--seed")?),
+      "--size" if ["serve", "bot", "env", "bench"]
+          .contains(&command.as_str()) => {
+        let size = value(&mut args, "--size")?;
+        let (w, h) = size.split_once('x')
+          .and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)))
+          .ok_or(SnakeError::Args(tr!("args.size").to_string()))?;
+        if w < MIN_FIELD_SIZE.0 || h < MIN_FIELD_SIZE.1 {
+          return Err(SnakeError::Args(tr!(
+            "args.size_too_small", MIN_FIELD_SIZE.0, MIN_FIELD_SIZE.1
+          )));
+        }
+        field_size = Size::from((w, h));
+      },
+      _ if ["join", "watch"].contains(&command.as_str()) &&
+          address.is_none() && !arg.starts_with('-') =>
+        address = Some(arg),
+      _ => return Err(SnakeError::Args(tr!("args.unknown", arg)))
+    }
+  }
+
+  if options.players + options.bots.len() > MAX_PLAYERS {
+    return Err(SnakeError::Args(tr!("args.too_many_players", MAX_PLAYERS)));
+  }
+
+  match command.as_str() {
+    "serve" => {
+      if let Some(level) = &options.level {
+        level.check_bounds(field_size)?;
+      }
+      Ok(Command::Serve { bind, port, field_size, options })
+    },
+    "bot" => {
+      let cmd = cmd
+        .ok_or(SnakeError::Args(tr!("args.bot_cmd").to_string()))?;
+      if let Some(level) = options.level.as_ref().filter(|_| headless) {
+        level.check_bounds(field_size)?;
+      }
+      Ok(Command::Bot { cmd, timeout, headless, field_size, options })
+    },
+    "bench" => {
+      let ai = ai
+        .ok_or(SnakeError::Args(tr!("args.bench_ai").to_string()))?;
+      let pilot = match Pilot::from(ai.as_str()) {
+        Pilot::External { cmd, .. } => Pilot::External { cmd, timeout },
+        builtin => builtin
+      };
+      if let Some(level) = &options.level {
+        level.check_bounds(field_size)?;
+      }
+      Ok(Command::Bench {
+        pilot, games, seed_start, jobs, json, field_size, options
+      })
+    },
+    "env" => {
+      if let Some(level) = &options.level {
+        level.check_bounds(field_size)?;
+      }
+      Ok(Command::Env {
+        episodes,
+        seed: options.seed.unwrap_or(0),
+        field_size,
+        options
+      })
+    },
+    "join" => {
+      let address = address
+        .ok_or(SnakeError::Args(tr!("args.join").to_string()))?;
+      Ok(Command::Join(address))
+    },
+    "watch" => {
+      let address = address
+        .ok_or(SnakeError::Args(tr!("args.watch").to_string()))?;
+      Ok(Command::Watch(Address::parse(&address)?))
+    },
+    "stats" => Ok(Command::Stats(format)),
+    _ if resume => Ok(Command::Resume(broadcast)),
+    _ => Ok(Command::Play(options, broadcast))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use snake::ai::Difficulty;
+  use snake::{Pilot, Address};
+  use snake::stats::Format;
+  use super::{parse, take_option, Command};
+
+  fn args(line: &str) -> Vec<String> {
+    line.split_whitespace().map(String::from).collect()
+  }
+
+  #[test]
+  fn test_parse_play() {
+    match parse(args("--arcade --two-players --bot greedy")).unwrap() {
+      Command::Play(options, _) => {
+        assert!(options.arcade);
+        assert_eq!(options.players, 2);
+        assert_eq!(options.bots, vec![Difficulty::Greedy]);
+      },
+      _ => panic!("expected play")
+    }
+    assert!(parse(args("--two-players --bot greedy --bot greedy")).is_ok());
+    let crowded = "--two-players --bot greedy --bot greedy --bot greedy";
+    assert!(parse(args(crowded)).is_err());
+
+    assert!(matches!(parse(args("")).unwrap(), Command::Menu));
+    assert!(matches!(parse(args("play")).unwrap(), Command::Play(_, None)));
+    assert!(matches!(parse(args("play --resume")).unwrap(), Command::Resume(None)));
+    assert!(matches!(parse(args("stats")).unwrap(), Command::Stats(Format::Table)));
+    assert!(matches!(parse(args("stats --csv")).unwrap(), Command::Stats(Format::Csv)));
+    assert!(!parse(args("stats")).unwrap().renders());
+    assert!(parse(args("bot --cmd ./bot --headless")).unwrap().renders());
+
+    let mut line = args("--lang en bench --ai greedy");
+    assert_eq!(take_option(&mut line, "--lang").unwrap().as_deref(), Some("en"));
+    assert_eq!(line, args("bench --ai greedy"));
+    assert!(take_option(&mut args("play --lang"), "--lang").is_err());
+
+    match parse(args("--demo")).unwrap() {
+      Command::Play(options, _) => assert!(options.demo),
+      _ => panic!("expected play")
+    }
+  }
+
+  #[test]
+  fn test_parse_serve_and_join() {
+    match parse(args("serve --port 9000 --players 2 --size 50x20")).unwrap() {
+      Command::Serve { bind, port, field_size, options } => {
+        assert_eq!(bind, "127.0.0.1");
+        assert_eq!(port, 9000);
+        assert_eq!(field_size.width, 50);
+        assert_eq!(field_size.height, 20);
+        assert_eq!(options.players, 2);
+      },
+      _ => panic!("expected serve")
+    }
+
+    match parse(args("join localhost:9000")).unwrap() {
+      Command::Join(address) => assert_eq!(address, "localhost:9000"),
+      _ => panic!("expected join")
+    }
+
+    match parse(args("play --broadcast tcp:localhost:9000")).unwrap() {
+      Command::Play(_, Some(address)) =>
+        assert_eq!(address, Address::Tcp("localhost:9000".to_string())),
+      _ => panic!("expected play")
+    }
+    assert!(matches!(parse(args("watch 127.0.0.1:9000")).unwrap(),
+      Command::Watch(Address::Tcp(_))));
+
+    match parse(args("bot --cmd ./my_bot --timeout 20 --headless")).unwrap() {
+      Command::Bot { cmd, timeout, headless, .. } => {
+        assert_eq!(cmd, "./my_bot");
+        assert_eq!(timeout.as_millis(), 20);
+        assert!(headless);
+      },
+      _ => panic!("expected bot")
+    }
+
+    match parse(args("bench --ai greedy --games 5 --seed-start 7 --json")).unwrap() {
+      Command::Bench { pilot, games, seed_start, json, .. } => {
+        assert!(matches!(pilot, Pilot::Builtin(Difficulty::Greedy)));
+        assert_eq!(games, 5);
+        assert_eq!(seed_start, 7);
+        assert!(json);
+      },
+      _ => panic!("expected bench")
+    }
+
+    match parse(args("env --episodes 50 --seed 9 --size 10x8")).unwrap() {
+      Command::Env { episodes, seed, field_size, .. } => {
+        assert_eq!(episodes, 50);
+        assert_eq!(seed, 9);
+        assert_eq!(field_size.width, 10);
+      },
+      _ => panic!("expected env")
+    }
+  }
+
+  #[test]
+  fn test_parse_errors() {
+    assert!(parse(args("--port 10")).is_err());
+    assert!(parse(args("serve --port x")).is_err());
+    assert!(parse(args("serve --demo")).is_err());
+    assert!(parse(args("serve --size 10")).is_err());
+    assert!(parse(args("env --size 0x0")).is_err());
+    assert!(parse(args("bench --ai greedy --size 4x4")).is_err());
+    assert!(parse(args("bench --ai greedy --size 10x5")).is_ok());
+    assert!(parse(args("join")).is_err());
+    assert!(parse(args("watch")).is_err());
+    assert!(parse(args("serve --broadcast :9000")).is_err());
+    assert!(parse(args("bot --headless")).is_err());
+    assert!(parse(args("bench --games 5")).is_err());
+    assert!(parse(args("stats --games 5")).is_err());
+    assert!(parse(args("--bot smart")).is_err());
+    assert!(parse(args("--bogus")).is_err());
+
+    let file = std::env::temp_dir().join("snake-cli-test.lvl");
+    std::fs::write(&file, "brick 30 8").unwrap();
+    let level = format!("--level {}", file.display());
+    assert!(parse(args(&format!("env {level} --size 20x10"))).is_err());
+    assert!(parse(args(&format!("env {level} --size 40x10"))).is_ok());
+    std::fs::remove_file(&file).unwrap();
+  }
+}
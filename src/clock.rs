@@ -0,0 +1,87 @@
+use std::time::{Duration, Instant};
+
+#[derive(Copy, Clone, Debug)]
+pub struct Clock {
+  running_since: Option<Instant>,
+  banked: Duration,
+  origin: Duration
+}
+
+impl Clock {
+  pub fn new() -> Self {
+    Self::resumed(Duration::ZERO)
+  }
+
+  pub fn resumed(elapsed: Duration) -> Self {
+    Self { running_since: None, banked: elapsed, origin: elapsed }
+  }
+
+  pub fn is_running(&self) -> bool {
+    self.running_since.is_some()
+  }
+
+  pub fn start(&mut self) {
+    if self.running_since.is_none() {
+      self.running_since = Some(Instant::now());
+    }
+  }
+
+  pub fn pause(&mut self) {
+    if let Some(since) = self.running_since.take() {
+      self.banked += since.elapsed();
+    }
+  }
+
+  pub fn reset(&mut self) {
+    let running = self.is_running();
+    *self = Self::new();
+    if running {
+      self.start();
+    }
+  }
+
+  pub fn elapsed(&self) -> Duration {
+    self.banked + self.running_since.map_or(Duration::ZERO, |since| since.elapsed())
+  }
+
+  pub fn seconds(&self) -> f64 {
+    self.elapsed().as_secs_f64()
+  }
+
+  pub fn played(&self) -> f64 {
+    self.elapsed().saturating_sub(self.origin).as_secs_f64()
+  }
+}
+
+impl Default for Clock {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{thread::sleep, time::Duration};
+  use super::Clock;
+
+  #[test]
+  fn test_clock_skips_pauses() {
+    let mut clock = Clock::resumed(Duration::from_millis(1500));
+    assert_eq!(clock.seconds(), 1.5);
+    assert_eq!(clock.played(), 0.0);
+
+    clock.start();
+    sleep(Duration::from_millis(20));
+    clock.pause();
+    let paused = clock.elapsed();
+    assert!(paused >= Duration::from_millis(1520));
+
+    sleep(Duration::from_millis(20));
+    assert_eq!(clock.elapsed(), paused);
+    assert!(clock.played() >= 0.02);
+
+    clock.reset();
+    assert_eq!(clock.elapsed(), Duration::ZERO);
+    assert!(!clock.is_running());
+  }
+}
@@ -0,0 +1,316 @@
+use crate::snake::Direction;
+use crate::ui::dimensions::{Pos, Size};
+use crate::world::{World, GameOptions, Event, DeathCause};
+
+pub const CHANNELS: usize = 7;
+pub const FEATURES: usize = 14;
+pub const ACTIONS: usize = 5;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Action {
+  Keep, Up, Down, Left, Right
+}
+
+impl TryFrom<usize> for Action {
+  type Error = usize;
+
+  fn try_from(index: usize) -> std::result::Result<Self, Self::Error> {
+    match index {
+      0 => Ok(Action::Keep),
+      1 => Ok(Action::Up),
+      2 => Ok(Action::Down),
+      3 => Ok(Action::Left),
+      4 => Ok(Action::Right),
+      _ => Err(index)
+    }
+  }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Rewards {
+  pub food: f64,
+  pub death: f64,
+  pub step: f64
+}
+
+impl Default for Rewards {
+  fn default() -> Self {
+    Self { food: 1.0, death: -1.0, step: -0.01 }
+  }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Observation {
+  pub width: usize,
+  pub height: usize,
+  pub grid: Vec<f32>,
+  pub features: Vec<f32>
+}
+
+impl Observation {
+  pub fn cell(&self, channel: usize, x: usize, y: usize) -> f32 {
+    self.grid[(channel * self.height + y) * self.width + x]
+  }
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct Info {
+  pub score: u64,
+  pub length: usize,
+  pub apples: u64,
+  pub steps: u64,
+  pub ticks: u64,
+  pub death: Option<DeathCause>
+}
+
+pub struct Env {
+  field_size: Size,
+  options: GameOptions,
+  rewards: Rewards,
+  max_steps: u64,
+  world: World,
+  info: Info
+}
+
+impl Env {
+  pub fn new(field_size: Size, options: GameOptions) -> Self {
+    let options = GameOptions {
+      players: 1,
+      demo: false,
+      seed: Some(0),
+      ..options
+    };
+
+    Self {
+      world: World::new(field_size, &options),
+      field_size,
+      options,
+      rewards: Rewards::default(),
+      max_steps: 10_000,
+      info: Info::default()
+    }
+  }
+
+  pub fn rewards(mut self, rewards: Rewards) -> Self {
+    self.rewards = rewards;
+    self
+  }
+
+  pub fn max_steps(mut self, max_steps: u64) -> Self {
+    self.max_steps = max_steps;
+    self
+  }
+
+  pub fn get_world(&self) -> &World {
+    &self.world
+  }
+
+  pub fn reset(&mut self, seed: u64) -> Observation {
+    self.options.seed = Some(seed);
+    self.world = World::new(self.field_size, &self.options);
+    self.info = Info {
+      length: 1,
+      ..Info::default()
+    };
+
+    self.observe()
+  }
+
+  fn is_done(&self) -> bool {
+    self.world.is_over() || self.info.steps >= self.max_steps
+  }
+
+  pub fn step(&mut self, action: Action) -> (Observation, f64, bool, Info) {
+    if self.is_done() {
+      return (self.observe(), 0.0, true, self.info.clone());
+    }
+
+    let dir = match action {
+      Action::Keep  => None,
+      Action::Up    => Some(Direction::Up),
+      Action::Down  => Some(Direction::Down),
+      Action::Left  => Some(Direction::Left),
+      Action::Right => Some(Direction::Right)
+    };
+    if let Some(dir) = dir {
+      self.world.players[0].push_direction(dir);
+    }
+
+    let mut reward = self.rewards.step;
+    let mut moved = false;
+
+    while !moved && !self.world.is_over() {
+      for event in self.world.tick() {
+        match event {
+          Event::SnakeMoved { player: 0, .. } => moved = true,
+          Event::AppleEaten { player: 0, .. } => {
+            reward += self.rewards.food;
+            self.info.apples += 1;
+          },
+          Event::PlayerDied { player: 0, .. } => reward += self.rewards.death,
+          _ => ()
+        }
+      }
+    }
+
+    let player = &self.world.players[0];
+    self.info.steps += 1;
+    self.info.ticks = self.world.tick;
+    self.info.score = player.score.total();
+    self.info.length = player.snake.get_parts().len();
+    self.info.death = player.death;
+
+    (self.observe(), reward, self.is_done(), self.info.clone())
+  }
+
+  fn index(&self, pos: &Pos) -> Option<(usize, usize)> {
+    let x = pos.x.checked_sub(2)? as usize;
+    let y = pos.y.checked_sub(1)? as usize;
+    let (width, height) = (
+      self.field_size.width as usize, self.field_size.height as usize
+    );
+
+    if x < width && y < height { Some((x, y)) } else { None }
+  }
+
+  fn is_dangerous(&self, pos: &Pos) -> bool {
+    let field = &self.world.field;
+
+    field.has_brick(pos) || field.has_hazard(pos) ||
+      self.world.players.iter().enumerate()
+        .filter(|(_, p)| p.is_alive())
+        .any(|(i, p)| {
+          let parts = p.snake.get_parts();
+          let parts = if i == 0 { &parts[..parts.len() - 1] } else { parts };
+          parts.iter().any(|part| part.get_pos() == *pos)
+        })
+  }
+
+  pub fn observe(&self) -> Observation {
+    let (width, height) = (
+      self.field_size.width as usize, self.field_size.height as usize
+    );
+    let mut grid = vec![0.0; CHANNELS * width * height];
+    let mut set = |channel: usize, pos: &Pos, value: f32| {
+      if let Some((x, y)) = self.index(pos) {
+        grid[(channel * height + y) * width + x] = value;
+      }
+    };
+
+    let world = &self.world;
+    let me = &world.players[0];
+
+    for (i, part) in me.snake.get_parts().iter().enumerate() {
+      set(if i == 0 { 0 } else { 1 }, &part.get_pos(), 1.0);
+    }
+
+    let apple = &world.field.apple;
+    set(2, &apple.get_pos(), apple.get_value() as f32 / 20.0);
+
+    for brick in &world.field.bricks {
+      set(3, &brick.get_pos(), 1.0);
+    }
+    for hazard in &world.field.hazards {
+      set(4, &hazard.get_pos(), 1.0);
+    }
+    for portal in &world.field.portals {
+      for end in portal.get_ends() {
+        set(5, &end, 1.0);
+      }
+    }
+    for other in world.players.iter().skip(1).filter(|p| p.is_alive()) {
+      for part in other.snake.get_parts() {
+        set(6, &part.get_pos(), 1.0);
+      }
+    }
+
+    let head = me.snake.get_head_pos();
+    let dir = me.snake.get_direction();
+    let apple_pos = apple.get_pos();
+    let (w, h) = (width as f32, height as f32);
+
+    let mut features = vec![
+      (head.x - 2) as f32 / w,
+      (head.y - 1) as f32 / h,
+      (dir == Direction::Up) as u8 as f32,
+      (dir == Direction::Down) as u8 as f32,
+      (dir == Direction::Left) as u8 as f32,
+      (dir == Direction::Right) as u8 as f32,
+      (apple_pos.x as f32 - head.x as f32) / w,
+      (apple_pos.y as f32 - head.y as f32) / h,
+      me.snake.get_parts().len() as f32 / (w * h),
+      me.boost as u8 as f32
+    ];
+
+    for next_dir in [
+      Direction::Up, Direction::Down, Direction::Left, Direction::Right
+    ] {
+      let next = next_dir.next_pos(head, self.field_size);
+      features.push(self.is_dangerous(&next) as u8 as f32);
+    }
+
+    Observation { width, height, grid, features }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::ui::dimensions::Size;
+  use crate::world::GameOptions;
+  use super::{Env, Action, Rewards, CHANNELS, FEATURES};
+
+  #[test]
+  fn test_reset_is_seeded() {
+    let mut env = Env::new(Size::from((20, 10)), GameOptions::default());
+    let a = env.reset(3);
+    assert_eq!(a.grid.len(), CHANNELS * 20 * 10);
+    assert_eq!(a.features.len(), FEATURES);
+
+    env.step(Action::Keep);
+    assert_eq!(env.reset(3), a);
+    let heads = (0..10)
+      .flat_map(|y| (0..20).map(move |x| (x, y)))
+      .filter(|&(x, y)| a.cell(0, x, y) == 1.0)
+      .count();
+    assert_eq!(heads, 1);
+  }
+
+  #[test]
+  fn test_step_rewards() {
+    let rewards = Rewards { food: 5.0, death: -10.0, step: -0.5 };
+    let mut env = Env::new(Size::from((20, 10)), GameOptions::default())
+      .rewards(rewards)
+      .max_steps(50);
+    env.reset(1);
+
+    let mut apples = 0;
+    loop {
+      let (_, reward, done, info) = env.step(Action::Keep);
+      let mut expected = -0.5;
+      if info.apples > apples {
+        expected += 5.0;
+      }
+      if info.death.is_some() {
+        expected -= 10.0;
+      }
+      assert_eq!(reward, expected);
+      apples = info.apples;
+
+      if done {
+        assert!(info.death.is_some() || info.steps == 50);
+        break;
+      }
+    }
+
+    let (_, reward, done, _) = env.step(Action::Up);
+    assert!(done);
+    assert_eq!(reward, 0.0);
+  }
+
+  #[test]
+  fn test_action_from_index() {
+    assert_eq!(Action::try_from(0), Ok(Action::Keep));
+    assert_eq!(Action::try_from(4), Ok(Action::Right));
+    assert!(Action::try_from(5).is_err());
+  }
+}
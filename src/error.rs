@@ -1,11 +1,19 @@
 use std::error;
 use std::fmt;
 
+use crate::tr;
+
 pub type Result<T> = std::result::Result<T, SnakeError>;
 
 #[derive(Debug)]
 pub enum SnakeError {
   Dimension(u16, u16),
+  Level(usize, String),
+  LevelBounds(u16, u16),
+  LevelFull,
+  Save(usize, String),
+  Args(String),
+  Network(String),
   Parse(std::io::Error)
 }
 
@@ -13,7 +21,21 @@ impl fmt::Display for SnakeError {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     match *self {
       SnakeError::Dimension(w, h)=>
-            write!(f, "Минимальный размер терминала {} столбцов {} строк", w, h),
+            write!(f, "{}", tr!("error.dimension", w, h)),
+      SnakeError::Level(line, ref msg) =>
+            write!(f, "{}", tr!("error.level", line, msg)),
+      SnakeError::LevelBounds(x, y) =>
+            write!(f, "{}", tr!("error.level_bounds", x, y)),
+      SnakeError::LevelFull =>
+            write!(f, "{}", tr!("error.level_full")),
+      SnakeError::Save(0, ref msg) =>
+            write!(f, "{}", tr!("error.save", msg)),
+      SnakeError::Save(line, ref msg) =>
+            write!(f, "{}", tr!("error.save_line", line, msg)),
+      SnakeError::Args(ref msg) =>
+            write!(f, "{}", tr!("error.args", msg)),
+      SnakeError::Network(ref msg) =>
+            write!(f, "{}", tr!("error.network", msg)),
       SnakeError::Parse(ref e) => e.fmt(f),
     }
   }
@@ -1,19 +1,23 @@
 use rand::Rng;
-use crossterm::style::Color;
 
 use crate::ui::{
   dimensions::{Pos, Size},
   Drawable,
+  theme,
   ui_items::Symbol
 };
 
 use std::any::Any;
 
+const FOOD_ATTEMPTS: usize = 100;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
 pub enum FoodType {
   GreenApple, GoldApple, Brick
 }
 
-pub trait Food {
+pub trait Food: Send {
+  fn get_type(&self) -> FoodType;
   fn get_symbol(&self) -> Symbol;
   fn get_value(&self) -> u16;
   fn get_pos(&self) -> Pos;
@@ -28,10 +32,13 @@ impl Drawable for Box<dyn Food> {
 
 struct GreenApple(Pos);
 impl Food for GreenApple {
+  fn get_type(&self) -> FoodType { FoodType::GreenApple }
+
   fn get_symbol(&self) -> Symbol {
+    let theme = theme::current();
     Symbol::new(self.0)
-      .ch('◉')
-      .color(Color::Green)
+      .ch(theme.glyphs.green_apple)
+      .color(theme.colors.green_apple)
   }
 
   fn get_value(&self) -> u16 { 10 }
@@ -43,10 +50,13 @@ impl Food for GreenApple {
 
 struct GoldApple(Pos);
 impl Food for GoldApple {
+  fn get_type(&self) -> FoodType { FoodType::GoldApple }
+
   fn get_symbol(&self) -> Symbol {
+    let theme = theme::current();
     Symbol::new(self.0)
-      .ch('◉')
-      .color(Color::Yellow)
+      .ch(theme.glyphs.gold_apple)
+      .color(theme.colors.gold_apple)
   }
 
   fn get_value(&self) -> u16 { 20 }
@@ -58,10 +68,13 @@ impl Food for GoldApple {
 
 struct Brick(Pos);
 impl Food for Brick {
+  fn get_type(&self) -> FoodType { FoodType::Brick }
+
   fn get_symbol(&self) -> Symbol {
+    let theme = theme::current();
     Symbol::new(self.0)
-      .ch('▬')
-      .color(Color::Red)
+      .ch(theme.glyphs.brick)
+      .color(theme.colors.brick)
   }
 
   fn get_value(&self) -> u16 { 0 }
@@ -72,12 +85,11 @@ impl Food for Brick {
 }
 
 pub fn generate_food(
-  field_size: &Size, edible: bool, snake_pos: &Pos
+  rng: &mut impl Rng, field_size: &Size, edible: bool, snake_pos: &Pos
 ) -> Box<dyn Food> {
-  let mut rng = rand::thread_rng();
   let mut pos = Pos::from((0, 0));
 
-  loop {
+  for _ in 0..FOOD_ATTEMPTS {
     pos.x = rng.gen_range(3..=field_size.width);
     pos.y = rng.gen_range(2..=field_size.height);
 
@@ -101,10 +113,14 @@ pub fn generate_food(
 }
 
 pub fn get_food_with_type(food_type: FoodType) -> Box<dyn Food> {
+  get_food_at(food_type, Pos::from((0, 0)))
+}
+
+pub fn get_food_at(food_type: FoodType, pos: Pos) -> Box<dyn Food> {
   match food_type {
-    FoodType::GreenApple => Box::new(GreenApple(Pos::from((0, 0)))),
-    FoodType::GoldApple  => Box::new(GoldApple(Pos::from((0, 0)))),
-    FoodType::Brick      => Box::new(Brick(Pos::from((0, 0))))
+    FoodType::GreenApple => Box::new(GreenApple(pos)),
+    FoodType::GoldApple  => Box::new(GoldApple(pos)),
+    FoodType::Brick      => Box::new(Brick(pos))
   }
 }
 
@@ -143,9 +159,10 @@ mod tests {
   fn test_generate_food() {
     let snake_pos = Pos::from((5, 5));
     let field_size = Size::from((10, 10));
+    let rng = &mut rand::thread_rng();
 
     for _ in 0..10 {
-      let apple = generate_food(&field_size, true, &snake_pos);
+      let apple = generate_food(rng, &field_size, true, &snake_pos);
       match apple.as_any().downcast_ref::<GreenApple>() {
         Some(_) => (),
         None => {
@@ -156,15 +173,25 @@ mod tests {
         },
       };
 
-      assert_eq!(apple.get_pos().is_overlaps(&snake_pos), false);
+      assert!(!apple.get_pos().is_overlaps(&snake_pos));
 
-      let brick = generate_food(&field_size, false, &snake_pos);
+      let brick = generate_food(rng, &field_size, false, &snake_pos);
       match brick.as_any().downcast_ref::<Brick>() {
         Some(_) => (),
         None => panic!("brick isn't a Brick!"),
       };
 
-      assert_eq!(brick.get_pos().is_overlaps(&snake_pos), false);
+      assert!(!brick.get_pos().is_overlaps(&snake_pos));
     }
   }
-}
\ No newline at end of file
+
+  #[test]
+  fn test_generate_food_without_room() {
+    let snake_pos = Pos::from((3, 2));
+    let field_size = Size::from((3, 2));
+    let rng = &mut rand::thread_rng();
+
+    let apple = generate_food(rng, &field_size, true, &snake_pos);
+    assert!(apple.get_pos() == snake_pos);
+  }
+}
@@ -1,26 +1,39 @@
-mod game_action;
+pub mod game_action;
+pub mod pause;
 
-use game_action::{KeyAction, KeyController};
-
-use crate::snake::{
-  Snake, Direction
+use game_action::{
+  KeyAction, KeyController,
+  poll_event, poll_key_press, boost_mode, ReleaseReports
 };
-
-use crate::food::{
-  Food, generate_food
+use pause::{PauseMenu, PauseCommand};
+
+use crate::bot::BotProcess;
+use crate::clock::Clock;
+use crate::net::{ServerMessage, broadcast::Broadcaster};
+use crate::save::{self, SavedGame};
+use crate::settings::{Settings, BoostMode};
+use crate::snapshot::Snapshot;
+use crate::splits::Splits;
+use crate::stats::{self, Stats};
+use crate::tr;
+
+use crate::snake::Direction;
+
+use crate::world::{
+  World, GameOptions, Outcome, Event
 };
 
 use crate::ui::{
-  UI,
-  dimensions::Size,
-  ui_items::Symbol
+  UI, Click,
+  dimensions::{Pos, Size},
+  renderer::Renderer,
+  theme
 };
 
 use std::{
-  collections::LinkedList,
   io::Result,
   thread::{sleep, self},
-  time::Duration,
+  time::{Duration, Instant},
   sync::{
     Arc, Mutex, Barrier,
     atomic::{AtomicBool, Ordering}
@@ -29,49 +42,162 @@ use std::{
 
 use crossterm::{
   terminal,
-  style::Color::*
+  event::{Event as InputEvent, KeyCode, KeyEventKind, MouseButton, MouseEventKind},
+  style::Color
 };
 
+const GAME_OVER_WAIT: Duration = Duration::from_secs(10);
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Finish {
+  Over, Interrupted, Saved, Menu
+}
+
 #[derive(Clone)]
 pub struct Game {
   barrier: Arc<Barrier>,
   stop_bool: Arc<AtomicBool>,
   pause: Arc<AtomicBool>,
-  boost: Arc<AtomicBool>,
-  score: u16,
+  over: Arc<AtomicBool>,
+  finish: Arc<Mutex<Finish>>,
+  demo: bool,
+  options: GameOptions,
+  settings: Arc<Mutex<Settings>>,
+  pilot: Option<Arc<Mutex<BotProcess>>>,
+  broadcast: Option<Broadcaster>,
+  stats: Arc<Mutex<Stats>>,
+  tracked: bool,
+  clock: Arc<Mutex<Clock>>,
+  splits: Option<Arc<Mutex<Splits>>>,
   ui: Arc<Mutex<UI>>,
-  snake: Arc<Mutex<Snake>>,
-  sequence: Arc<Mutex<LinkedList<Direction>>>,
-  field_size: Size,
+  world: Arc<Mutex<World>>,
   terminal_size: Size
 }
 
 impl Game {
-  pub fn new(ui: UI) -> Self {
-    use rand::Rng;
-    let mut rng = rand::thread_rng();
-    let dir = match rng.gen_range(0..4) {
-      0 => Direction::Up,
-      1 => Direction::Down,
-      2 => Direction::Left,
-      _ => Direction::Right,
+  pub fn new(ui: UI, options: GameOptions) -> Self {
+    let world = World::new(ui.field_size, &options);
+    Self::with_world(ui, world, options)
+  }
+
+  pub fn resume(ui: UI, saved: SavedGame) -> Self {
+    let options = GameOptions {
+      players: saved.world.humans(),
+      bots: saved.world.players.iter().filter_map(|p| p.bot).collect(),
+      ..saved.options
     };
 
+    let mut game = Self::with_world(ui, saved.world, options);
+    *game.clock.lock().unwrap() =
+      Clock::resumed(Duration::from_secs_f64(saved.time));
+    game.splits = None;
+    game.pause.store(saved.paused, Ordering::Release);
+    game
+  }
+
+  fn with_world(mut ui: UI, mut world: World, options: GameOptions) -> Self {
+    Self::style_players(&mut world, 0);
+    ui.set_players(&Self::human_colors(&world));
+    let tracked = !options.demo && world.humans() > 0;
+    let splits = (tracked && world.players.len() == 1)
+      .then(|| Arc::new(Mutex::new(Splits::new(world.field_size, &options))));
+
     Game {
       barrier: Arc::new(Barrier::new(3)),
       stop_bool: Arc::new(AtomicBool::new(false)),
       pause: Arc::new(AtomicBool::new(false)),
-      boost: Arc::new(AtomicBool::new(false)),
-      score: 0,
-      field_size: ui.field_size,
-      snake: Arc::new(Mutex::new(Snake::new(ui.field_size, dir))),
-      sequence: Arc::new(Mutex::new(LinkedList::new())),
+      over: Arc::new(AtomicBool::new(false)),
+      finish: Arc::new(Mutex::new(Finish::Over)),
+      demo: options.demo,
+      options,
+      settings: Arc::new(Mutex::new(Settings::default())),
+      pilot: None,
+      broadcast: None,
+      stats: Arc::new(Mutex::new(Stats::new())),
+      tracked,
+      clock: Arc::new(Mutex::new(Clock::new())),
+      splits,
+      world: Arc::new(Mutex::new(world)),
       ui: Arc::new(Mutex::new(ui)),
       terminal_size: Size::from(terminal::size().unwrap())
     }
   }
 
+  pub fn pilot(mut self, bot: BotProcess) -> Self {
+    self.pilot = Some(Arc::new(Mutex::new(bot)));
+    self.tracked = false;
+    self.splits = None;
+    self
+  }
+
+  pub fn broadcast(mut self, broadcaster: Broadcaster) -> Self {
+    let header = {
+      let world = self.world.lock().unwrap();
+      ServerMessage::Watch {
+        field_size: world.field_size,
+        players: world.players.len()
+      }
+    };
+    broadcaster.announce(&header);
+    self.broadcast = Some(broadcaster);
+    self.publish_state();
+    self
+  }
+
+  pub fn settings(self, settings: Settings) -> Self {
+    *self.settings.lock().unwrap() = settings;
+    theme::set(settings.theme());
+    {
+      let mut world = self.world.lock().unwrap();
+      let mut ui = self.ui.lock().unwrap();
+      Self::paint_players(&mut world, &settings);
+      ui.set_players(&Self::human_colors(&world));
+      ui.set_keys(
+        settings.keys, settings.layout, Self::holds_boost(&settings)
+      ).unwrap();
+    }
+    self
+  }
+
+  pub fn get_settings(&self) -> Settings {
+    *self.settings.lock().unwrap()
+  }
+
+  fn human_colors(world: &World) -> Vec<Color> {
+    world.players.iter()
+      .filter(|p| p.bot.is_none())
+      .map(|p| p.snake.get_head_color())
+      .collect()
+  }
+
+  fn style_players(world: &mut World, palette: usize) {
+    let theme = theme::current();
+    for (i, player) in world.players.iter_mut().enumerate() {
+      let (head, body) = theme.player_colors(i + palette);
+      player.snake.set_theme(theme);
+      player.snake.set_colors(head, body);
+      player.snake.set_boost(player.boost);
+    }
+  }
+
+  fn paint_players(world: &mut World, settings: &Settings) {
+    Self::style_players(world, settings.palette);
+    for player in world.players.iter_mut() {
+      if player.bot.is_none() {
+        player.set_input(settings.input_depth, settings.input_mode);
+      }
+    }
+  }
+
   pub fn run(&mut self) {
+    {
+      let world = self.world.lock().unwrap();
+      self.ui.lock().unwrap().init(&world).unwrap();
+    }
+    if !self.pause.load(Ordering::Acquire) {
+      self.clock.lock().unwrap().start();
+    }
+
     let threads = vec![
       Self::time_update,
       Self::snake_update,
@@ -79,33 +205,68 @@ impl Game {
       Self::terminal_size_checker
     ];
 
+    let mut handles = Vec::new();
     for thread in threads {
       let shared_self = self.clone();
 
-      let _ = thread::spawn(move || {
+      handles.push(thread::spawn(move || {
         let local_self = &mut shared_self.clone();
 
         thread(local_self)
-      });
+      }));
     }
 
     self.barrier.wait();
+
+    for handle in handles {
+      let _ = handle.join();
+    }
+
+    if self.tracked {
+      let _ = stats::commit(&self.get_stats());
+    }
+    if let Some(splits) = &self.splits {
+      let _ = splits.lock().unwrap().store();
+    }
+  }
+
+  pub fn get_stats(&self) -> Stats {
+    let mut stats = *self.stats.lock().unwrap();
+    stats.play_time += self.clock.lock().unwrap().played();
+    if self.get_finish() != Finish::Saved {
+      stats.games += 1;
+    }
+    stats
+  }
+
+  pub fn get_finish(&self) -> Finish {
+    *self.finish.lock().unwrap()
+  }
+
+  pub fn is_interrupted(&self) -> bool {
+    self.get_finish() != Finish::Over
+  }
+
+  fn stop(&self, finish: Finish) {
+    *self.finish.lock().unwrap() = finish;
+    self.stop_bool.store(true, Ordering::Release);
   }
 
   fn time_update(&mut self) -> Result<()> {
-    let (mut time, delay) = (0.0f64, 0.1f64);
+    let mut shown = None;
 
     loop {
-      if !self.pause.load(Ordering::Acquire) {
+      let time = self.clock.lock().unwrap().seconds();
+      if shown != Some(time) {
         self.ui.lock().unwrap().print_time(&time)?;
-        time += delay;
+        shown = Some(time);
       }
 
       if self.stop_bool.load(Ordering::Acquire) {
         break;
       }
       else {
-        sleep(Duration::from_millis(100));
+        sleep(Duration::from_millis(50));
       }
     }
 
@@ -113,238 +274,493 @@ impl Game {
     Ok(())
   }
 
-  fn init_food(&self) -> Result<(Box<dyn Food>, Vec<Box<dyn Food>>)> {
-    let apple;    
-    let mut bricks = Vec::new();
-    
-    let head_pos = self.snake.lock().unwrap().get_head_pos();
-    let density = self.field_size.width as u64 * 
-    self.field_size.height as u64 / 100;
-
-    for _ in 0..density {
-      bricks.push(generate_food(
-        &self.field_size, false, &head_pos
-      ));
-    }
-
-    apple = generate_food(
-      &self.field_size, true, &head_pos
-    );
-
-    self.ui.lock().unwrap().print_stats(&self.score, &0)?;
-    self.ui.lock().unwrap().draw::<Snake>(&self.snake.lock().unwrap())?;
-    self.ui.lock().unwrap().draw(&apple)?;
-    self.ui.lock().unwrap().draw_vec(&bricks)?;
-
-    Ok((apple, bricks))
-  } 
-
   fn snake_update(&mut self) -> Result<()> {
-    let mut _boost = false;
-
-    let (mut apple, mut bricks) = self.init_food()?;
+    if self.demo {
+      self.ui.lock().unwrap()
+        .print_popup_message(tr!("game.press_any_key"))?;
+    }
 
     loop {
-      while self.pause.load(Ordering::Acquire) {
+      while self.pause.load(Ordering::Acquire) &&
+          !self.stop_bool.load(Ordering::Acquire) {
         sleep(Duration::from_millis(50));
       }
-        
-      if self.boost.load(Ordering::Acquire) {
-        sleep(Duration::from_millis(150));
-      }
-      else {
-        sleep(Duration::from_millis(200));
-      }
 
-      if let Some(dir) = self.sequence.lock().unwrap().pop_front() {
-        self.snake.lock().unwrap().set_direction(dir)
+      let tick_ms = self.settings.lock().unwrap().speed.tick_ms();
+      sleep(Duration::from_millis(tick_ms));
+
+      if self.stop_bool.load(Ordering::Acquire) {
+        break;
       }
 
-      if !self.stop_bool.load(Ordering::Acquire) {
-        self.snake
-          .lock().unwrap()
-          .update(self.ui.clone())?;
-        self.ui.lock().unwrap().draw::<Snake>(
-          &self.snake
-            .lock().unwrap()
-        )?;
+      if let Some(pilot) = &self.pilot {
+        let state = BotProcess::request(&self.world.lock().unwrap(), 0);
+        let reply = state.and_then(|state| pilot.lock().unwrap().ask(&state));
+        if let Some(dir) = reply {
+          self.world.lock().unwrap().players[0].push_direction(dir);
+        }
       }
-      else {
-        break;
+
+      let events = {
+        let mut world = self.world.lock().unwrap();
+        let events = world.tick();
+        if self.tracked {
+          self.stats.lock().unwrap().record(&world, &events);
+        }
+        events
+      };
+      {
+        let world = self.world.lock().unwrap();
+        self.ui.lock().unwrap().render(&world, &events)?;
       }
+      self.publish_state();
+      self.check_splits(&events)?;
 
-      self.collision_check(&mut apple, &mut bricks)?;
+      if self.world.lock().unwrap().is_over() {
+        self.game_over()?;
+      }
     }
 
     self.barrier.wait();
     Ok(())
   }
 
-  fn collision_check(&mut self, apple: &mut Box<dyn Food>,
-      bricks: &mut Vec<Box<dyn Food>>) -> Result<()> {
+  fn publish(&self, message: ServerMessage) {
+    if let Some(broadcaster) = &self.broadcast {
+      broadcaster.publish(&message);
+    }
+  }
 
-    if self.snake.lock().unwrap().check_self_eaten() {
-      self.ui.lock().unwrap()
-        .print_popup_message("Сам себя съел!")?;
+  fn publish_state(&self) {
+    if self.broadcast.is_some() {
+      let snapshot = Snapshot::from(&*self.world.lock().unwrap());
+      self.publish(ServerMessage::State(snapshot));
+    }
+  }
+
+  fn check_splits(&mut self, events: &[Event]) -> Result<()> {
+    let Some(splits) = &self.splits else {
+      return Ok(());
+    };
+
+    for event in events {
+      if let Event::AppleEaten { player: 0, .. } = event {
+        let apples = self.world.lock().unwrap().players[0].snake
+          .get_parts().len() - 1;
+        if Splits::is_due(apples) {
+          let time = self.clock.lock().unwrap().seconds();
+          let split = splits.lock().unwrap().split(time);
+          self.ui.lock().unwrap().print_split(Some(&split))?;
+        }
+      }
+    }
+
+    Ok(())
+  }
+
+  fn game_over(&mut self) -> Result<()> {
+    self.clock.lock().unwrap().pause();
+    let world = self.world.lock().unwrap();
+    let mut ui = self.ui.lock().unwrap();
+    let buttons = !self.demo;
+    let outcome = world.outcome();
+
+    if world.players.len() == 1 {
+      let player = &world.players[0];
+      let message = player.death.map_or("", |cause| cause.message());
+      let rows: Vec<_> = player.score.breakdown().into_iter()
+        .map(|(name, value)| (name.to_string(), value))
+        .collect();
+
+      ui.print_popup_message(message)?;
+      ui.print_game_over(&rows, buttons)?;
+    }
+    else {
+      let message = match outcome {
+        Outcome::Winner(i) => match world.players[i].bot {
+          Some(difficulty) => tr!(
+            "game.bot_won", i + 1 - world.humans(), difficulty.name()
+          ),
+          None => tr!("game.player_won", i + 1)
+        },
+        Outcome::Draw => tr!("game.draw").to_string()
+      };
+      let rows: Vec<_> = world.players.iter().enumerate()
+        .map(|(i, p)| (world.player_name(i), p.score.total()))
+        .collect();
+
+      ui.print_popup_message(&message)?;
+      ui.print_game_over(&rows, buttons)?;
+    }
+
+    drop(ui);
+    drop(world);
+    self.publish(ServerMessage::Over(outcome));
 
+    if self.demo {
       self.stop_bool.store(true, Ordering::Release);
       sleep(Duration::from_secs(3));
+      return Ok(());
     }
 
-    if self.snake.lock().unwrap().check_pos(&apple.get_pos()) {
-      self.food_update(apple, bricks)?;
+    self.over.store(true, Ordering::Release);
+    let shown = Instant::now();
+    while self.over.load(Ordering::Acquire) &&
+        !self.stop_bool.load(Ordering::Acquire) {
+      if shown.elapsed() >= GAME_OVER_WAIT {
+        self.stop(Finish::Over);
+      }
+      sleep(Duration::from_millis(50));
     }
 
-    for brick in bricks {
-      if self.snake.lock().unwrap().check_pos(&brick.get_pos()) {
-        self.ui.lock().unwrap()
-          .print_popup_message("Съел кирпич!")?;
+    Ok(())
+  }
 
-        self.stop_bool.store(true, Ordering::Release);
-        sleep(Duration::from_secs(3));
+  fn game_over_event(&mut self, event: &InputEvent) -> Result<()> {
+    let click = match event {
+      InputEvent::Key(key) if key.kind != KeyEventKind::Press => return Ok(()),
+      InputEvent::Key(key) if key.code == KeyCode::Enter => Some(Click::Restart),
+      InputEvent::Key(_) => None,
+      InputEvent::Mouse(mouse)
+          if mouse.kind == MouseEventKind::Down(MouseButton::Left) => {
+        let pos = Pos::from((mouse.column, mouse.row));
+        match self.ui.lock().unwrap().click(pos) {
+          Some(Click::Pause) | None => return Ok(()),
+          click => click
+        }
+      },
+      _ => return Ok(())
+    };
+
+    match click {
+      Some(Click::Restart) => {
+        self.pause.store(true, Ordering::Release);
+        self.restart()?;
+        self.over.store(false, Ordering::Release);
+        self.countdown()
+      },
+      Some(Click::Menu) => {
+        self.stop(Finish::Menu);
+        Ok(())
+      },
+      _ => {
+        self.stop(Finish::Over);
+        Ok(())
       }
     }
+  }
 
-    Ok(())
+  fn steer_toward(&mut self, pos: Pos) {
+    let mut world = self.world.lock().unwrap();
+    let size = world.field_size;
+    let inside = (2..=size.width + 1).contains(&pos.x) &&
+      (1..=size.height).contains(&pos.y);
+    if !inside {
+      return;
+    }
+
+    let Some(player) = world.players.iter_mut().find(|p| p.bot.is_none()) else {
+      return;
+    };
+    let (dir, head) = (player.snake.get_direction(), player.snake.get_head_pos());
+    if let Some(turn) = dir.turn_toward(head, pos) {
+      player.push_direction(turn);
+    }
   }
 
-  fn boost_mode_toggle(&mut self) {
-    let boost = self.boost.load(Ordering::Acquire);
-    let color = if !boost { Cyan } else { Green };
-    self.snake.lock().unwrap().set_head_color(color);
-    self.boost.store(!boost, Ordering::Release);
+  fn repaint(&mut self) -> Result<()> {
+    let world = self.world.lock().unwrap();
+    let mut ui = self.ui.lock().unwrap();
+    ui.clear_field()?;
+    ui.init(&world)
   }
 
-  fn pause_mode_toggle(&mut self) -> Result<()>{
-    let pause = self.pause.load(Ordering::Acquire);
-    if !pause {
-      self.ui.lock().unwrap().print_popup_message("Пауза")?;
+  fn open_pause_menu(&mut self) -> Result<PauseMenu> {
+    self.pause.store(true, Ordering::Release);
+    self.clock.lock().unwrap().pause();
+
+    let settings = self.get_settings();
+    let menu = PauseMenu::new(&settings, self.pilot.is_none());
+    self.ui.lock().unwrap().print_window(menu.window(&settings))?;
+
+    Ok(menu)
+  }
+
+  fn apply_settings(&mut self) -> Result<()> {
+    let settings = self.get_settings();
+    let _ = settings.store();
+    theme::set(settings.theme());
+
+    let mut world = self.world.lock().unwrap();
+    let mut ui = self.ui.lock().unwrap();
+
+    Self::paint_players(&mut world, &settings);
+    ui.set_players(&Self::human_colors(&world));
+    ui.set_keys(
+      settings.keys, settings.layout, Self::holds_boost(&settings)
+    )?;
+    ui.init(&world)
+  }
+
+  fn restart(&mut self) -> Result<()> {
+    let settings = self.get_settings();
+    let mut world = self.world.lock().unwrap();
+    *world = World::new(world.field_size, &self.options);
+    Self::paint_players(&mut world, &settings);
+    let mut clock = self.clock.lock().unwrap();
+    if self.tracked {
+      let mut stats = self.stats.lock().unwrap();
+      stats.games += 1;
+      stats.play_time += clock.played();
     }
-    else {
-      self.ui.lock().unwrap().clear_popup_message()?;
+    clock.reset();
+    if let Some(splits) = &self.splits {
+      splits.lock().unwrap().reset();
     }
-    self.pause.store(!pause, Ordering::Release);
 
-    Ok(())
+    let mut ui = self.ui.lock().unwrap();
+    ui.print_split(None)?;
+    ui.print_time(&clock.seconds())
   }
 
-  fn fetch_event(&mut self) -> Result<()> {
-    let key_controller = KeyController::new();
-    loop {
-      let action = key_controller.fetch_action()?;
-
-      if !self.pause.load(Ordering::Acquire) {
-        let sequence = self.sequence.clone();
-        let mut sequence = sequence.lock().unwrap();
-        let mut dir = None;
-
-        match action {
-          KeyAction::None | KeyAction::Pause => (),
-          KeyAction::MoveUp    => dir = Some(Direction::Up),
-          KeyAction::MoveDown  => dir = Some(Direction::Down),
-          KeyAction::MoveLeft  => dir = Some(Direction::Left),
-          KeyAction::MoveRight => dir = Some(Direction::Right),
-          KeyAction::Boost     => self.boost_mode_toggle(),
-          KeyAction::Exit => {
-            self.ui.lock().unwrap()
-              .print_popup_message("Прерывание...")?;
-
-            self.stop_bool.store(true, Ordering::Release);
-            break;
-          }
-        }
+  fn countdown(&mut self) -> Result<()> {
+    self.repaint()?;
+    self.publish_state();
 
-        if let Some(d) = dir {
-          if let Some(last) = sequence.back() {
-            if *last != d {
-              sequence.push_back(d)
-            }
-          }
-          else {
-            sequence.push_back(d)
-          }
-        }
+    for n in (1..=3).rev() {
+      if self.stop_bool.load(Ordering::Acquire) {
+        return Ok(());
       }
 
-      if let KeyAction::Pause = action {
-        self.pause_mode_toggle().unwrap();
+      {
+        let ui = self.ui.lock().unwrap();
+        ui.clear_popup_message()?;
+        ui.print_popup_message(&tr!("game.countdown", n))?;
       }
+      self.publish(ServerMessage::Countdown(n));
+      sleep(Duration::from_secs(1));
     }
 
+    self.ui.lock().unwrap().clear_popup_message()?;
+    self.clock.lock().unwrap().start();
+    self.pause.store(false, Ordering::Release);
+
     Ok(())
   }
 
-  fn food_update(&mut self, apple: &mut Box<dyn Food>,
-      bricks: &mut Vec<Box<dyn Food>>) -> Result<()> {
-    
-    let mut ui = self.ui.lock().unwrap();
+  fn save_and_quit(&mut self) -> Result<bool> {
+    let stored = {
+      let world = self.world.lock().unwrap();
+      let time = self.clock.lock().unwrap().seconds();
+      let paused = self.pause.load(Ordering::Acquire);
+      save::store(&world, &self.options, time, paused)
+    };
 
-    self.score += apple.get_value();
+    let ui = self.ui.lock().unwrap();
+    ui.clear_popup_message()?;
+    match stored {
+      Ok(()) => {
+        ui.print_popup_message(tr!("game.saved"))?;
+        self.stop(Finish::Saved);
+        Ok(true)
+      },
+      Err(err) => {
+        ui.print_popup_message(&tr!("game.save_failed", err))?;
+        Ok(false)
+      }
+    }
+  }
 
-    ui.print_stats(
-      &self.score,
-      &(self.snake
-        .lock().unwrap()
-        .get_parts().len() as u16)
-    )?;
+  fn wait_any_key(&mut self) -> Result<()> {
+    while !self.stop_bool.load(Ordering::Acquire) {
+      if poll_key_press(Duration::from_millis(100))? {
+        self.stop(Finish::Interrupted);
+      }
+    }
 
-    self.snake
-      .lock().unwrap()
-      .add_part();
-    
-    let snake_pos = self.snake
-      .lock().unwrap()
-      .get_head_pos();
+    Ok(())
+  }
 
-    loop {
-      *apple = generate_food(
-        &self.field_size, true, &snake_pos
-      );
-
-      if !self.snake
-        .lock().unwrap()
-        .check_pos(&apple.get_pos()) {
-        
-        break;
-      }
+  fn pause_event(&mut self, menu: &mut PauseMenu,
+      event: &InputEvent) -> Result<Option<PauseCommand>> {
+
+    let origin = self.ui.lock().unwrap()
+      .window_origin(&menu.window(&self.get_settings()));
+    let (command, window, resized) = {
+      let mut settings = self.settings.lock().unwrap();
+      let before = menu.window(&settings);
+      let command = match event {
+        InputEvent::Key(key) => menu.key(key.code, &mut settings),
+        InputEvent::Mouse(mouse) => menu.mouse(origin, *mouse, &mut settings),
+        _ => None
+      };
+      let after = menu.window(&settings);
+      let resized = (before.width(), before.height()) !=
+        (after.width(), after.height());
+      (command, after, resized)
+    };
+
+    match command {
+      None | Some(PauseCommand::Apply) => {
+        if command.is_some() {
+          self.apply_settings()?;
+        }
+        if resized {
+          self.repaint()?;
+        }
+        self.ui.lock().unwrap().print_window(window)?;
+      },
+      Some(PauseCommand::Resume) => self.countdown()?,
+      Some(PauseCommand::Restart) => {
+        self.restart()?;
+        self.countdown()?;
+      },
+      Some(PauseCommand::Save) => {
+        self.save_and_quit()?;
+      },
+      Some(PauseCommand::Quit) => self.stop(Finish::Menu)
     }
 
-    for i in 0..bricks.len() {
-      ui.draw(&Symbol::new(bricks[i].get_pos()))?;
+    Ok(command)
+  }
+
+  fn fetch_event(&mut self) -> Result<()> {
+    if self.demo {
+      return self.wait_any_key();
+    }
 
-      'same:
-      loop {
-        bricks[i] = generate_food(
-          &self.field_size, false, &snake_pos
-        );
+    let players = self.world.lock().unwrap().humans();
+    let settings = self.get_settings();
+    let mut key_controller = KeyController::new(
+      players, settings.keys, settings.layout
+    );
+    let mut releases = ReleaseReports::default();
+    self.sync_boost_mode(&mut releases)?;
+    let mut pause_menu = None;
+    if self.pause.load(Ordering::Acquire) {
+      pause_menu = Some(self.open_pause_menu()?);
+    }
 
-        if self.snake
-          .lock().unwrap()
-          .check_pos(&bricks[i].get_pos()) ||
-            apple.get_pos() == bricks[i].get_pos() {
-          
+    while !self.stop_bool.load(Ordering::Acquire) {
+      let Some(event) = poll_event(Duration::from_millis(100))? else {
+        continue;
+      };
+      if let InputEvent::Mouse(mouse) = event {
+        if mouse.kind == MouseEventKind::Moved {
           continue;
         }
-        
-        for j in 0..bricks.len() {
-          if i != j && bricks[i].get_pos() == bricks[j].get_pos() {
-            continue 'same;
+      }
+
+      if let InputEvent::Key(key_event) = event {
+        let (player, action) = key_controller.action(key_event.code);
+        if key_event.kind == KeyEventKind::Release {
+          if releases.is_enabled() && action == KeyAction::Boost &&
+              self.pilot.is_none() {
+            self.world.lock().unwrap().players[player].set_boost(false);
           }
+          continue;
         }
-        break;
       }
-    }
 
-    ui.draw_vec(&bricks)?;
-    ui.draw(apple)?;
+      if self.over.load(Ordering::Acquire) {
+        self.game_over_event(&event)?;
+        continue;
+      }
+
+      if let Some(menu) = &mut pause_menu {
+        if let InputEvent::Key(key) = event {
+          if key.kind == KeyEventKind::Repeat {
+            continue;
+          }
+        }
+
+        match self.pause_event(menu, &event)? {
+          Some(PauseCommand::Apply) => {
+            let settings = self.get_settings();
+            key_controller = KeyController::new(
+              players, settings.keys, settings.layout
+            );
+            self.sync_boost_mode(&mut releases)?;
+          },
+          Some(PauseCommand::Resume) | Some(PauseCommand::Restart) =>
+            pause_menu = None,
+          _ => ()
+        }
+        continue;
+      }
+
+      let key_event = match event {
+        InputEvent::Key(key_event) => key_event,
+        InputEvent::Mouse(mouse)
+            if mouse.kind == MouseEventKind::Down(MouseButton::Left) => {
+          let pos = Pos::from((mouse.column, mouse.row));
+          if self.ui.lock().unwrap().click(pos) == Some(Click::Pause) {
+            pause_menu = Some(self.open_pause_menu()?);
+          }
+          else if self.get_settings().mouse_steer && self.pilot.is_none() {
+            self.steer_toward(pos);
+          }
+          continue;
+        },
+        _ => continue
+      };
+
+      let (player, action) = key_controller.action(key_event.code);
+      if key_event.kind == KeyEventKind::Repeat && action == KeyAction::Boost {
+        continue;
+      }
+      let action = match action {
+        KeyAction::MoveUp | KeyAction::MoveDown | KeyAction::MoveLeft |
+          KeyAction::MoveRight | KeyAction::Boost
+          if self.pilot.is_some() => KeyAction::None,
+        action => action
+      };
+
+      let mut world = self.world.lock().unwrap();
+      let player = &mut world.players[player];
+
+      match action {
+        KeyAction::None => (),
+        KeyAction::MoveUp    => player.push_direction(Direction::Up),
+        KeyAction::MoveDown  => player.push_direction(Direction::Down),
+        KeyAction::MoveLeft  => player.push_direction(Direction::Left),
+        KeyAction::MoveRight => player.push_direction(Direction::Right),
+        KeyAction::Boost if releases.is_enabled() => player.set_boost(true),
+        KeyAction::Boost     => player.toggle_boost(),
+        KeyAction::Pause => {
+          drop(world);
+          pause_menu = Some(self.open_pause_menu()?);
+        },
+        KeyAction::Save => {
+          drop(world);
+          if self.pilot.is_none() {
+            self.save_and_quit()?;
+          }
+        },
+        KeyAction::Exit => {
+          drop(world);
+          self.ui.lock().unwrap()
+            .print_popup_message(tr!("game.interrupted"))?;
+          self.stop(Finish::Interrupted);
+        }
+      }
+    }
 
     Ok(())
   }
 
+  fn holds_boost(settings: &Settings) -> bool {
+    boost_mode(settings.boost) == BoostMode::Hold
+  }
+
+  fn sync_boost_mode(&self, releases: &mut ReleaseReports) -> Result<()> {
+    releases.set(Self::holds_boost(&self.get_settings()))
+  }
+
   fn terminal_size_checker(&mut self) -> Result<()> {
-    let terminal_size = self.terminal_size.clone();
+    let terminal_size = self.terminal_size;
 
-    loop {
+    while !self.stop_bool.load(Ordering::Acquire) {
       if terminal_size != Size::from(terminal::size()?) {
         self.stop_bool.store(true, Ordering::Release);
         break;
@@ -362,4 +778,4 @@ impl Drop for Game {
   fn drop(&mut self) {
     self.ui.lock().unwrap().disable_raw_mode();
   }
-}
\ No newline at end of file
+}
@@ -1,68 +1,163 @@
-use crossterm::event::{
-  KeyCode, read, Event, KeyEventKind
+use crossterm::{
+  execute,
+  terminal,
+  event::{
+    KeyCode, poll, read, Event, KeyEventKind,
+    KeyboardEnhancementFlags,
+    PushKeyboardEnhancementFlags, PopKeyboardEnhancementFlags
+  }
 };
 
 use std::{
-  io::Result,
-  collections::HashMap
+  io::{Result, stdout},
+  collections::HashMap,
+  sync::OnceLock,
+  time::Duration
 };
 
-#[derive(Clone, Copy)]
+use crate::settings::{BoostMode, KeyScheme, Layout};
+
+static RELEASES: OnceLock<bool> = OnceLock::new();
+
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum KeyAction {
   MoveUp, MoveDown,
   MoveLeft, MoveRight,
-  Boost, Pause, Exit,
+  Boost, Pause, Save, Exit,
   None
 }
 
 pub struct KeyController {
-  keys: HashMap<KeyCode, KeyAction>
+  keys: HashMap<KeyCode, (usize, KeyAction)>
 }
 
 impl KeyController {
-  pub fn new() -> Self {
-    let keys = HashMap::from([
-      (KeyCode::Char('w'), KeyAction::MoveUp),
-      (KeyCode::Char('ц'), KeyAction::MoveUp),
-      (KeyCode::Up,        KeyAction::MoveUp),
-      (KeyCode::Char('s'), KeyAction::MoveDown),
-      (KeyCode::Char('ы'), KeyAction::MoveDown),
-      (KeyCode::Down,      KeyAction::MoveDown),
-      (KeyCode::Char('a'), KeyAction::MoveLeft),
-      (KeyCode::Char('ф'), KeyAction::MoveLeft),
-      (KeyCode::Left,      KeyAction::MoveLeft),
-      (KeyCode::Char('d'), KeyAction::MoveRight),
-      (KeyCode::Char('в'), KeyAction::MoveRight),
-      (KeyCode::Right,     KeyAction::MoveRight),
-      (KeyCode::Char('b'), KeyAction::Boost),
-      (KeyCode::Char('и'), KeyAction::Boost),
-      (KeyCode::Char('p'), KeyAction::Pause),
-      (KeyCode::Char('з'), KeyAction::Pause),
-      (KeyCode::Pause,     KeyAction::Pause),
-      (KeyCode::Esc,       KeyAction::Exit)
+  pub fn new(players: usize, scheme: KeyScheme, layout: Layout) -> Self {
+    let second = if players > 1 { 1 } else { 0 };
+    let arrows = [
+      KeyCode::Up, KeyCode::Down,
+      KeyCode::Left, KeyCode::Right,
+      KeyCode::Enter
+    ];
+    let moves = [
+      KeyAction::MoveUp, KeyAction::MoveDown,
+      KeyAction::MoveLeft, KeyAction::MoveRight
+    ];
+
+    let mut keys = HashMap::from([
+      (KeyCode::Up,    KeyAction::MoveUp),
+      (KeyCode::Down,  KeyAction::MoveDown),
+      (KeyCode::Left,  KeyAction::MoveLeft),
+      (KeyCode::Right, KeyAction::MoveRight),
+      (KeyCode::Pause, KeyAction::Pause),
+      (KeyCode::Enter, KeyAction::Boost),
+      (KeyCode::Esc,   KeyAction::Exit)
     ]);
 
+    let letters = scheme.letters().into_iter().zip(moves).chain([
+      ('b', KeyAction::Boost),
+      ('p', KeyAction::Pause),
+      ('q', KeyAction::Save)
+    ]);
+    for (key, action) in letters {
+      for ch in layout.chars(key) {
+        keys.insert(KeyCode::Char(ch), action);
+      }
+    }
+
+    let keys = keys.into_iter()
+      .filter(|(key, _)| players > 1 || *key != KeyCode::Enter)
+      .map(|(key, action)| {
+        let player = if arrows.contains(&key) { second } else { 0 };
+        (key, (player, action))
+      })
+      .collect();
+
     Self { keys }
   }
 
-  pub fn fetch_action(&self) -> Result<KeyAction> {
-    let event = read()?;
-    let mut action = KeyAction::None;
-
-    match event {
-      Event::Key(key_event) => {
-        if let KeyEventKind::Press = key_event.kind {
-          if let Some(key) = self.keys.get(&key_event.code) {
-            action = key.clone()
-          }
-        }
-        else {
-          action = KeyAction::None
-        }
-      },
-      _ => action = KeyAction::None
+  pub fn action(&self, code: KeyCode) -> (usize, KeyAction) {
+    self.keys.get(&code).copied().unwrap_or((0, KeyAction::None))
+  }
+
+  pub fn poll_action(&self,
+      timeout: Duration) -> Result<Option<(usize, KeyAction)>> {
+
+    Ok(poll_key(timeout)?.map(|code| self.action(code)))
+  }
+
+  pub fn fetch_action(&self) -> Result<(usize, KeyAction)> {
+    match read()? {
+      Event::Key(key_event) if key_event.kind == KeyEventKind::Press =>
+        Ok(self.action(key_event.code)),
+      _ => Ok((0, KeyAction::None))
+    }
+  }
+}
+
+pub fn poll_event(timeout: Duration) -> Result<Option<Event>> {
+  if !poll(timeout)? {
+    return Ok(None);
+  }
+
+  read().map(Some)
+}
+
+pub fn poll_key(timeout: Duration) -> Result<Option<KeyCode>> {
+  match poll_event(timeout)? {
+    Some(Event::Key(key_event)) if key_event.kind == KeyEventKind::Press =>
+      Ok(Some(key_event.code)),
+    _ => Ok(None)
+  }
+}
+
+pub fn supports_releases() -> bool {
+  *RELEASES.get_or_init(|| {
+    terminal::supports_keyboard_enhancement().unwrap_or(false)
+  })
+}
+
+pub fn boost_mode(mode: BoostMode) -> BoostMode {
+  if supports_releases() { mode } else { BoostMode::Toggle }
+}
+
+fn report_releases(enable: bool) -> Result<()> {
+  if enable {
+    execute!(stdout(), PushKeyboardEnhancementFlags(
+      KeyboardEnhancementFlags::REPORT_EVENT_TYPES
+    ))
+  }
+  else {
+    execute!(stdout(), PopKeyboardEnhancementFlags)
+  }
+}
+
+#[derive(Default)]
+pub struct ReleaseReports {
+  enabled: bool
+}
+
+impl ReleaseReports {
+  pub fn is_enabled(&self) -> bool {
+    self.enabled
+  }
+
+  pub fn set(&mut self, enable: bool) -> Result<()> {
+    if enable != self.enabled {
+      report_releases(enable)?;
+      self.enabled = enable;
     }
 
-    Ok(action)
+    Ok(())
+  }
+}
+
+impl Drop for ReleaseReports {
+  fn drop(&mut self) {
+    let _ = self.set(false);
   }
-}
\ No newline at end of file
+}
+
+pub fn poll_key_press(timeout: Duration) -> Result<bool> {
+  Ok(poll_key(timeout)?.is_some())
+}
@@ -0,0 +1,223 @@
+use crossterm::event::{KeyCode, MouseEvent, MouseEventKind, MouseButton};
+
+use crate::game::game_action::boost_mode;
+use crate::settings::Settings;
+use crate::tr;
+
+use crate::ui::{
+  menu::{Menu, MenuAction},
+  dimensions::Pos,
+  ui_items::PopupWindow
+};
+
+const SAVE_ITEM: usize = 4;
+const ITEMS: [&str; 6] = [
+  "pause.resume",
+  "pause.restart",
+  "pause.settings",
+  "pause.help",
+  "pause.save",
+  "pause.menu"
+];
+
+const HELP_LINES: [&str; 9] = [
+  "pause.help_boost",
+  "pause.help_pause",
+  "pause.help_save",
+  "pause.help_exit",
+  "pause.help_apples",
+  "pause.help_combo",
+  "pause.help_danger",
+  "pause.help_danger_end",
+  "pause.help_back"
+];
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum PauseCommand {
+  Resume, Restart, Apply, Save, Quit
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum Screen {
+  Main, Settings, Help
+}
+
+pub struct PauseMenu {
+  screen: Screen,
+  main: Menu,
+  settings: Menu,
+  can_save: bool
+}
+
+impl PauseMenu {
+  pub fn new(settings: &Settings, can_save: bool) -> Self {
+    let items: Vec<&str> = ITEMS.iter().enumerate()
+      .filter(|&(i, _)| can_save || i != SAVE_ITEM)
+      .map(|(_, item)| tr!(item))
+      .collect();
+
+    let mut menu = Self {
+      screen: Screen::Main,
+      main: Menu::new(tr!("pause.title"), &items).hint(tr!("pause.hint")),
+      settings: Menu::new(tr!("pause.settings"), &[])
+        .hint(tr!("settings.hint")),
+      can_save
+    };
+    menu.settings.set_items(Self::settings_items(settings));
+
+    menu
+  }
+
+  fn settings_items(settings: &Settings) -> Vec<String> {
+    vec![
+      tr!("settings.speed", settings.speed.name()),
+      tr!("settings.color", settings.color_name()),
+      tr!("settings.keys", settings.keys.name()),
+      tr!("settings.theme", settings.theme.name()),
+      tr!("settings.glyphs", settings.glyphs.name()),
+      tr!("settings.skin", settings.skin.name()),
+      tr!("settings.input_depth", settings.input_depth),
+      tr!("settings.input_mode", settings.input_mode.name()),
+      tr!("settings.boost", boost_mode(settings.boost).name()),
+      tr!("settings.layout", settings.layout.name()),
+      tr!("settings.mouse_steer", settings.mouse_steer_name()),
+      tr!("settings.back").to_string()
+    ]
+  }
+
+  pub fn key(&mut self, code: KeyCode,
+      settings: &mut Settings) -> Option<PauseCommand> {
+
+    match self.screen {
+      Screen::Main => match self.main.key(code) {
+        Some(MenuAction::Select(i)) => {
+          let item = if !self.can_save && i >= SAVE_ITEM { i + 1 } else { i };
+          match item {
+            0 => Some(PauseCommand::Resume),
+            1 => Some(PauseCommand::Restart),
+            2 => {
+              self.screen = Screen::Settings;
+              None
+            },
+            3 => {
+              self.screen = Screen::Help;
+              None
+            },
+            SAVE_ITEM => Some(PauseCommand::Save),
+            _ => Some(PauseCommand::Quit)
+          }
+        },
+        Some(_) => Some(PauseCommand::Resume),
+        None if matches!(code, KeyCode::Char(ch)
+            if settings.layout.chars('p').contains(&ch)) =>
+          Some(PauseCommand::Resume),
+        None => None
+      },
+      Screen::Settings => match self.settings.key(code) {
+        Some(MenuAction::Select(i)) if i < 11 => {
+          match i {
+            0 => settings.speed = settings.speed.next(),
+            1 => settings.next_palette(),
+            2 => settings.keys = settings.keys.next(),
+            3 => settings.theme = settings.theme.next(),
+            4 => settings.glyphs = settings.glyphs.next(),
+            5 => settings.skin = settings.skin.next(),
+            6 => settings.next_input_depth(),
+            7 => settings.input_mode = settings.input_mode.next(),
+            8 => settings.boost = boost_mode(settings.boost.next()),
+            9 => settings.layout = settings.layout.next(),
+            _ => settings.mouse_steer = !settings.mouse_steer
+          }
+          self.settings.set_items(Self::settings_items(settings));
+          Some(PauseCommand::Apply)
+        },
+        Some(_) => {
+          self.screen = Screen::Main;
+          None
+        },
+        None => None
+      },
+      Screen::Help => {
+        self.screen = Screen::Main;
+        None
+      }
+    }
+  }
+
+  pub fn mouse(&mut self, origin: Pos, event: MouseEvent,
+      settings: &mut Settings) -> Option<PauseCommand> {
+
+    let code = match event.kind {
+      MouseEventKind::ScrollUp => KeyCode::Up,
+      MouseEventKind::ScrollDown => KeyCode::Down,
+      MouseEventKind::Down(MouseButton::Left) => {
+        let menu = match self.screen {
+          Screen::Main => &mut self.main,
+          Screen::Settings => &mut self.settings,
+          Screen::Help => return self.key(KeyCode::Esc, settings)
+        };
+        let pos = Pos::from((event.column, event.row));
+        menu.set_selected(menu.item_at(origin, pos)?);
+        KeyCode::Enter
+      },
+      _ => return None
+    };
+
+    self.key(code, settings)
+  }
+
+  pub fn window(&self, settings: &Settings) -> PopupWindow {
+    match self.screen {
+      Screen::Main => self.main.window(),
+      Screen::Settings => self.settings.window(),
+      Screen::Help => {
+        let mut lines = vec![tr!("pause.help_move", settings.keys.name())];
+        lines.extend(HELP_LINES.iter().map(|key| tr!(key).to_string()));
+
+        PopupWindow::new(format!(" {} ", tr!("pause.help")), lines)
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crossterm::event::KeyCode;
+
+  use crate::settings::{Settings, Speed, KeyScheme};
+  use crate::ui::theme::{GlyphSet, Skin};
+  use super::{PauseMenu, PauseCommand};
+
+  #[test]
+  fn test_pause_menu() {
+    let mut settings = Settings::default();
+    let mut menu = PauseMenu::new(&settings, false);
+
+    assert_eq!(menu.key(KeyCode::Enter, &mut settings), Some(PauseCommand::Resume));
+    menu.key(KeyCode::Up, &mut settings);
+    assert_eq!(menu.key(KeyCode::Enter, &mut settings), Some(PauseCommand::Quit));
+
+    menu.key(KeyCode::Up, &mut settings);
+    menu.key(KeyCode::Up, &mut settings);
+    assert_eq!(menu.key(KeyCode::Enter, &mut settings), None);
+    assert_eq!(menu.key(KeyCode::Enter, &mut settings), Some(PauseCommand::Apply));
+    assert_eq!(settings.speed, Speed::Fast);
+    menu.key(KeyCode::Down, &mut settings);
+    menu.key(KeyCode::Down, &mut settings);
+    menu.key(KeyCode::Enter, &mut settings);
+    assert_eq!(settings.keys, KeyScheme::Esdf);
+    menu.key(KeyCode::Down, &mut settings);
+    menu.key(KeyCode::Down, &mut settings);
+    menu.key(KeyCode::Enter, &mut settings);
+    assert_eq!(settings.glyphs, GlyphSet::Unicode);
+    menu.key(KeyCode::Down, &mut settings);
+    menu.key(KeyCode::Enter, &mut settings);
+    assert_eq!(settings.skin, Skin::Directional);
+    menu.key(KeyCode::Down, &mut settings);
+    menu.key(KeyCode::Enter, &mut settings);
+    assert_eq!(settings.input_depth, 3);
+
+    assert_eq!(menu.key(KeyCode::Esc, &mut settings), None);
+    assert_eq!(menu.key(KeyCode::Esc, &mut settings), Some(PauseCommand::Resume));
+  }
+}
@@ -0,0 +1,273 @@
+use rand::Rng;
+
+use crate::snake::Direction;
+
+use crate::ui::{
+  dimensions::{Pos, Size},
+  Drawable,
+  theme,
+  ui_items::Symbol
+};
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum HazardKind {
+  Patrol, Creature, Rock
+}
+
+#[derive(Clone)]
+pub struct Hazard {
+  kind: HazardKind,
+  pos: Pos,
+  path: Vec<Direction>,
+  step: usize,
+  period: u16,
+  wait: u16
+}
+
+impl Hazard {
+  pub fn patrol(pos: Pos, path: Vec<Direction>) -> Self {
+    Self::new(HazardKind::Patrol, pos, path, 6)
+  }
+
+  pub fn creature(pos: Pos) -> Self {
+    Self::new(HazardKind::Creature, pos, Vec::new(), 8)
+  }
+
+  pub fn rock(pos: Pos, dir: Direction) -> Self {
+    Self::new(HazardKind::Rock, pos, vec![dir], 4)
+  }
+
+  pub fn of_kind(kind: HazardKind, pos: Pos) -> Self {
+    match kind {
+      HazardKind::Patrol   => Self::patrol(pos, Vec::new()),
+      HazardKind::Creature => Self::creature(pos),
+      HazardKind::Rock     => Self::rock(pos, Direction::Down)
+    }
+  }
+
+  pub fn restore(kind: HazardKind, pos: Pos,
+      path: Vec<Direction>, step: usize, wait: u16) -> Self {
+
+    let mut hazard = Self::of_kind(kind, pos);
+    hazard.path = path;
+    hazard.step = step;
+    hazard.wait = wait;
+    hazard
+  }
+
+  fn new(kind: HazardKind, pos: Pos,
+      path: Vec<Direction>, period: u16) -> Self {
+
+    Self { kind, pos, path, step: 0, period, wait: 0 }
+  }
+
+  pub fn get_kind(&self) -> HazardKind {
+    self.kind
+  }
+
+  pub fn get_pos(&self) -> Pos {
+    self.pos
+  }
+
+  pub fn get_symbol(&self) -> Symbol {
+    let theme = theme::current();
+    let (ch, color) = match self.kind {
+      HazardKind::Patrol   => (theme.glyphs.patrol, theme.colors.patrol),
+      HazardKind::Creature => (theme.glyphs.creature, theme.colors.creature),
+      HazardKind::Rock     => (theme.glyphs.rock, theme.colors.rock)
+    };
+
+    Symbol::new(self.pos).ch(ch).color(color)
+  }
+
+  pub fn get_path(&self) -> &[Direction] {
+    &self.path
+  }
+
+  pub fn get_step(&self) -> usize {
+    self.step
+  }
+
+  pub fn get_wait(&self) -> u16 {
+    self.wait
+  }
+
+  pub fn tick(&mut self) -> bool {
+    self.wait += 1;
+    if self.wait >= self.period {
+      self.wait = 0;
+      true
+    }
+    else {
+      false
+    }
+  }
+
+  pub fn step<F>(&mut self, rng: &mut impl Rng,
+      field_size: Size, target: Pos, blocked: F) -> Pos
+  where F: Fn(&Pos) -> bool, {
+    let old_pos = self.pos;
+
+    match self.kind {
+      HazardKind::Patrol | HazardKind::Rock => {
+        if let Some(dir) = self.path.get(self.step) {
+          self.pos = dir.next_pos(old_pos, field_size);
+          self.step = (self.step + 1) % self.path.len();
+        }
+      },
+      HazardKind::Creature => {
+        for dir in Self::chase_directions(rng, old_pos, target) {
+          let next = dir.next_pos(old_pos, field_size);
+          if !blocked(&next) {
+            self.pos = next;
+            break;
+          }
+        }
+      }
+    }
+
+    old_pos
+  }
+
+  fn chase_directions(rng: &mut impl Rng,
+      from: Pos, target: Pos) -> Vec<Direction> {
+
+    let horizontal = if target.x < from.x {
+      Direction::Left
+    } else {
+      Direction::Right
+    };
+    let vertical = if target.y < from.y {
+      Direction::Up
+    } else {
+      Direction::Down
+    };
+
+    let mut dirs = if from.x.abs_diff(target.x) >= from.y.abs_diff(target.y) {
+      vec![horizontal, vertical]
+    } else {
+      vec![vertical, horizontal]
+    };
+
+    if rng.gen_ratio(1, 4) {
+      dirs.swap(0, 1);
+    }
+    dirs.push(dirs[1].opposite());
+    dirs.push(dirs[0].opposite());
+
+    dirs
+  }
+}
+
+impl Drawable for Hazard {
+  fn draw(&self) -> std::io::Result<()> {
+    self.get_symbol().draw()
+  }
+}
+
+pub fn generate_hazard(rng: &mut impl Rng,
+    field_size: &Size, snake_pos: &Pos) -> Hazard {
+
+  let mut pos = Pos::from((0, 0));
+
+  loop {
+    pos.x = rng.gen_range(3..=field_size.width);
+    pos.y = rng.gen_range(2..=field_size.height);
+
+    if !pos.is_overlaps(snake_pos) {
+      break;
+    }
+  }
+
+  let dir = match rng.gen_range(0..4) {
+    0 => Direction::Up,
+    1 => Direction::Down,
+    2 => Direction::Left,
+    _ => Direction::Right,
+  };
+
+  match rng.gen_range(0..3) {
+    0 => {
+      let length = rng.gen_range(3..=8);
+      let mut path = vec![dir; length];
+      path.extend(vec![dir.opposite(); length]);
+      Hazard::patrol(pos, path)
+    },
+    1 => Hazard::creature(pos),
+    _ => Hazard::rock(pos, dir)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use rand::SeedableRng;
+
+  use crate::rng::GameRng;
+  use crate::snake::Direction;
+  use crate::ui::dimensions::{Pos, Size};
+  use super::{Hazard, HazardKind, generate_hazard};
+
+  #[test]
+  fn test_hazard_tick() {
+    let mut rock = Hazard::rock(Pos::from((5, 5)), Direction::Down);
+    assert!(!rock.tick());
+    assert!(!rock.tick());
+    assert!(!rock.tick());
+    assert!(rock.tick());
+    assert!(!rock.tick());
+  }
+
+  #[test]
+  fn test_patrol_path() {
+    let field_size = Size::from((10, 10));
+    let path = vec![Direction::Right, Direction::Right, Direction::Left, Direction::Left];
+    let mut patrol = Hazard::patrol(Pos::from((5, 5)), path);
+    let rng = &mut GameRng::seed_from_u64(1);
+
+    let old_pos = patrol.step(rng, field_size, Pos::from((0, 0)), |_| false);
+    assert!(old_pos == Pos::from((5, 5)));
+    patrol.step(rng, field_size, Pos::from((0, 0)), |_| false);
+    assert_eq!(patrol.get_pos().x, 7);
+    patrol.step(rng, field_size, Pos::from((0, 0)), |_| false);
+    patrol.step(rng, field_size, Pos::from((0, 0)), |_| false);
+    assert_eq!(patrol.get_pos().x, 5);
+  }
+
+  #[test]
+  fn test_rock_wraps() {
+    let field_size = Size::from((10, 4));
+    let mut rock = Hazard::rock(Pos::from((5, 4)), Direction::Down);
+    rock.step(&mut GameRng::seed_from_u64(1), field_size, Pos::from((0, 0)), |_| false);
+    assert_eq!(rock.get_pos().y, 1);
+  }
+
+  #[test]
+  fn test_creature_chase() {
+    let field_size = Size::from((20, 20));
+    let target = Pos::from((15, 5));
+    let rng = &mut GameRng::seed_from_u64(1);
+    let dirs = Hazard::chase_directions(rng, Pos::from((5, 5)), target);
+    assert_eq!(dirs.len(), 4);
+    assert!(dirs[..2].contains(&Direction::Right));
+    assert!(!dirs[..2].contains(&Direction::Left));
+
+    let mut creature = Hazard::creature(Pos::from((5, 5)));
+    creature.step(rng, field_size, target, |_| true);
+    assert!(creature.get_pos() == Pos::from((5, 5)));
+  }
+
+  #[test]
+  fn test_generate_hazard() {
+    let snake_pos = Pos::from((5, 5));
+    let field_size = Size::from((10, 10));
+    let rng = &mut GameRng::seed_from_u64(1);
+
+    for _ in 0..10 {
+      let hazard = generate_hazard(rng, &field_size, &snake_pos);
+      assert!(!hazard.get_pos().is_overlaps(&snake_pos));
+      if hazard.get_kind() == HazardKind::Patrol {
+        assert!(hazard.path.len() >= 6);
+      }
+    }
+  }
+}
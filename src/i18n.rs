@@ -0,0 +1,161 @@
+use std::{
+  collections::HashMap,
+  env, fmt, fs,
+  sync::OnceLock
+};
+
+use crate::error::{self, SnakeError};
+use crate::storage;
+
+const BUILTIN: [(&str, &str); 2] = [
+  ("ru", include_str!("../lang/ru.txt")),
+  ("en", include_str!("../lang/en.txt"))
+];
+const DEFAULT_LANG: &str = "ru";
+const FALLBACK_LANG: &str = "en";
+
+static CATALOG: OnceLock<Catalog> = OnceLock::new();
+
+#[macro_export]
+macro_rules! tr {
+  ($key:expr) => {
+    $crate::i18n::get($key)
+  };
+  ($key:expr, $($arg:expr),+ $(,)?) => {
+    $crate::i18n::format($crate::i18n::get($key), &[$(&$arg),+])
+  };
+}
+
+pub struct Catalog {
+  lang: String,
+  messages: HashMap<String, String>
+}
+
+impl Catalog {
+  pub fn load(lang: &str) -> Option<Self> {
+    let own = source(lang)?;
+    let mut messages = HashMap::new();
+
+    for lang in [DEFAULT_LANG, FALLBACK_LANG] {
+      if let Some(text) = source(lang) {
+        messages.extend(storage::parse_pairs(&text));
+      }
+    }
+    messages.extend(storage::parse_pairs(&own));
+
+    Some(Self { lang: lang.to_string(), messages })
+  }
+
+  pub fn get_lang(&self) -> &str {
+    &self.lang
+  }
+
+  pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+    self.messages.get(key).map(String::as_str).unwrap_or(key)
+  }
+}
+
+pub fn init(lang: Option<&str>) -> error::Result<()> {
+  let catalog = match lang {
+    Some(lang) => Catalog::load(lang).ok_or_else(|| SnakeError::Args(
+      tr!("args.lang", lang)
+    ))?,
+    None => detected()
+  };
+
+  let _ = CATALOG.set(catalog);
+  Ok(())
+}
+
+pub fn lang() -> &'static str {
+  catalog().get_lang()
+}
+
+pub fn get(key: &str) -> &str {
+  catalog().get(key)
+}
+
+pub fn format(template: &str, args: &[&dyn fmt::Display]) -> String {
+  let mut result = String::new();
+  let mut args = args.iter();
+  let mut rest = template;
+
+  while let Some(i) = rest.find("{}") {
+    result.push_str(&rest[..i]);
+    if let Some(arg) = args.next() {
+      result.push_str(&arg.to_string());
+    }
+    rest = &rest[i + 2..];
+  }
+
+  result.push_str(rest);
+  result
+}
+
+pub fn detect() -> String {
+  if cfg!(test) {
+    return DEFAULT_LANG.to_string();
+  }
+
+  ["LC_ALL", "LC_MESSAGES", "LANG"].iter()
+    .filter_map(|name| env::var(name).ok())
+    .find(|value| !value.is_empty())
+    .map(|value| locale_lang(&value))
+    .unwrap_or_else(|| DEFAULT_LANG.to_string())
+}
+
+fn catalog() -> &'static Catalog {
+  CATALOG.get_or_init(detected)
+}
+
+fn detected() -> Catalog {
+  Catalog::load(&detect())
+    .or_else(|| Catalog::load(DEFAULT_LANG))
+    .expect("встроенный каталог")
+}
+
+fn locale_lang(locale: &str) -> String {
+  let lang = locale.split(['_', '.', '@', '-'])
+    .next()
+    .unwrap_or_default()
+    .to_lowercase();
+
+  match lang.as_str() {
+    "" | "c" | "posix" => FALLBACK_LANG.to_string(),
+    _ => lang
+  }
+}
+
+fn source(lang: &str) -> Option<String> {
+  if lang.is_empty() || !lang.chars().all(|ch| ch.is_ascii_alphanumeric()) {
+    return None;
+  }
+
+  let path = storage::data_dir().join("lang").join(format!("{}.txt", lang));
+  if let Ok(text) = fs::read_to_string(path) {
+    return Some(text);
+  }
+
+  BUILTIN.iter()
+    .find(|(code, _)| *code == lang)
+    .map(|(_, text)| text.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_catalogs_have_same_keys() {
+    let keys = |text| {
+      let mut keys: Vec<String> = storage::parse_pairs(text).into_keys().collect();
+      keys.sort();
+      keys
+    };
+
+    assert_eq!(keys(BUILTIN[0].1), keys(BUILTIN[1].1));
+    assert_eq!(locale_lang("en_US.UTF-8"), "en");
+    assert_eq!(locale_lang("C"), "en");
+    assert_eq!(format("{} из {}", &[&1, &"двух"]), "1 из двух");
+  }
+}
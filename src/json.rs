@@ -0,0 +1,146 @@
+use crate::ui::dimensions::Pos;
+
+pub trait ToJson {
+  fn to_json(&self) -> String;
+}
+
+impl ToJson for str {
+  fn to_json(&self) -> String {
+    let mut out = String::from('"');
+
+    for ch in self.chars() {
+      match ch {
+        '"'  => out.push_str("\\\""),
+        '\\' => out.push_str("\\\\"),
+        '\n' => out.push_str("\\n"),
+        '\r' => out.push_str("\\r"),
+        '\t' => out.push_str("\\t"),
+        ch if (ch as u32) < 0x20 => {
+          out.push_str(&format!("\\u{:04x}", ch as u32))
+        },
+        ch => out.push(ch)
+      }
+    }
+
+    out.push('"');
+    out
+  }
+}
+
+impl ToJson for String {
+  fn to_json(&self) -> String {
+    self.as_str().to_json()
+  }
+}
+
+macro_rules! number_to_json {
+  ($($t:ty),*) => {
+    $(impl ToJson for $t {
+      fn to_json(&self) -> String {
+        self.to_string()
+      }
+    })*
+  };
+}
+
+number_to_json!(bool, u16, u32, u64, usize, i64);
+
+impl ToJson for f64 {
+  fn to_json(&self) -> String {
+    if self.is_finite() {
+      format!("{:.3}", self)
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+    }
+    else {
+      "null".to_string()
+    }
+  }
+}
+
+impl ToJson for Pos {
+  fn to_json(&self) -> String {
+    format!("[{},{}]", self.x, self.y)
+  }
+}
+
+impl<T: ToJson + ?Sized> ToJson for &T {
+  fn to_json(&self) -> String {
+    (**self).to_json()
+  }
+}
+
+impl<T: ToJson> ToJson for [T] {
+  fn to_json(&self) -> String {
+    let items: Vec<String> = self.iter().map(|item| item.to_json()).collect();
+    format!("[{}]", items.join(","))
+  }
+}
+
+impl<T: ToJson> ToJson for Vec<T> {
+  fn to_json(&self) -> String {
+    self.as_slice().to_json()
+  }
+}
+
+impl<T: ToJson> ToJson for Option<T> {
+  fn to_json(&self) -> String {
+    match self {
+      Some(value) => value.to_json(),
+      None => "null".to_string()
+    }
+  }
+}
+
+#[derive(Default)]
+pub struct Object {
+  fields: Vec<(String, String)>
+}
+
+impl Object {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn field<T: ToJson + ?Sized>(mut self, key: &str, value: &T) -> Self {
+    self.fields.push((key.to_json(), value.to_json()));
+    self
+  }
+}
+
+impl ToJson for Object {
+  fn to_json(&self) -> String {
+    let fields: Vec<String> = self.fields.iter()
+      .map(|(key, value)| format!("{}:{}", key, value))
+      .collect();
+    format!("{{{}}}", fields.join(","))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::ui::dimensions::Pos;
+  use super::{Object, ToJson};
+
+  #[test]
+  fn test_json_escape() {
+    assert_eq!("a\"b\\c\n".to_json(), r#""a\"b\\c\n""#);
+    assert_eq!("змея".to_json(), "\"змея\"");
+  }
+
+  #[test]
+  fn test_json_object() {
+    let object = Object::new()
+      .field("name", "snake")
+      .field("body", &vec![Pos::from((1, 2)), Pos::from((3, 4))])
+      .field("food", &None::<u64>)
+      .field("rate", &0.25)
+      .field("alive", &true);
+
+    assert_eq!(
+      object.to_json(),
+      r#"{"name":"snake","body":[[1,2],[3,4]],"food":null,"rate":0.25,"alive":true}"#
+    );
+  }
+}
@@ -0,0 +1,214 @@
+use std::{collections::HashSet, fs, path::Path};
+
+use crate::error::{self, SnakeError};
+use crate::hazard::{Hazard, HazardKind};
+use crate::portal::Portal;
+use crate::snake::Direction;
+use crate::snapshot::dir_token;
+use crate::tr;
+use crate::ui::dimensions::{Pos, Size};
+use crate::world::MAX_PLAYERS;
+
+#[derive(Clone)]
+pub struct Level {
+  pub name: String,
+  pub bricks: Vec<Pos>,
+  pub hazards: Vec<Hazard>,
+  pub portals: Vec<Portal>
+}
+
+impl Level {
+  pub fn load(path: &Path) -> error::Result<Level> {
+    Self::parse(&fs::read_to_string(path)?)
+  }
+
+  pub fn parse(source: &str) -> error::Result<Level> {
+    let mut level = Level {
+      name: String::new(),
+      bricks: Vec::new(),
+      hazards: Vec::new(),
+      portals: Vec::new()
+    };
+
+    for (i, line) in source.lines().enumerate() {
+      let line = line.trim();
+      if line.is_empty() || line.starts_with('#') {
+        continue;
+      }
+
+      let line_no = i + 1;
+      let mut words = line.split_whitespace();
+      let keyword = words.next().unwrap();
+
+      if keyword == "name" {
+        level.name = line["name".len()..].trim().to_string();
+        continue;
+      }
+
+      let pos = Self::parse_pos(&mut words, line_no)?;
+
+      match keyword {
+        "brick"    => level.bricks.push(pos),
+        "creature" => level.hazards.push(Hazard::creature(pos)),
+        "portal" => {
+          let exit = Self::parse_pos(&mut words, line_no)?;
+          let index = level.portals.len();
+          level.portals.push(Portal::new(pos, exit, index));
+        },
+        "patrol" => {
+          let path = Self::parse_path(words.next(), line_no)?;
+          level.hazards.push(Hazard::patrol(pos, path));
+        },
+        "rock" => {
+          let path = Self::parse_path(words.next(), line_no)?;
+          if path.len() != 1 {
+            return Err(SnakeError::Level(
+              line_no, tr!("level.rock_direction").to_string()
+            ));
+          }
+          level.hazards.push(Hazard::rock(pos, path[0]));
+        },
+        _ => return Err(SnakeError::Level(
+          line_no, tr!("level.unknown_object", keyword)
+        ))
+      }
+    }
+
+    Ok(level)
+  }
+
+  fn parse_pos<'a, I>(words: &mut I, line_no: usize) -> error::Result<Pos>
+  where I: Iterator<Item = &'a str>, {
+    let mut coord = || -> error::Result<u16> {
+      words.next()
+        .and_then(|w| w.parse::<u16>().ok())
+        .ok_or(SnakeError::Level(
+          line_no, tr!("level.expected_pos").to_string()
+        ))
+    };
+
+    let x = coord()?;
+    let y = coord()?;
+
+    match (x.checked_add(2), y.checked_add(1)) {
+      (Some(x), Some(y)) => Ok(Pos::from((x, y))),
+      _ => Err(SnakeError::Level(line_no, tr!("level.pos_too_large").to_string()))
+    }
+  }
+
+  fn parse_path(word: Option<&str>, line_no: usize)
+      -> error::Result<Vec<Direction>> {
+
+    let word = word.ok_or(SnakeError::Level(
+      line_no, tr!("level.expected_path").to_string()
+    ))?;
+
+    word.chars()
+      .map(|ch| Direction::try_from(ch).map_err(|ch|
+        SnakeError::Level(line_no, tr!("level.unknown_direction", ch))
+      ))
+      .collect()
+  }
+
+  pub fn encode(&self) -> String {
+    let coord = |pos: Pos| format!("{} {}", pos.x - 2, pos.y - 1);
+    let mut lines = vec![format!("name {}", self.name).trim_end().to_string()];
+
+    lines.extend(self.bricks.iter().map(|pos| format!("brick {}", coord(*pos))));
+    for hazard in &self.hazards {
+      let path: String = hazard.get_path().iter().map(|d| dir_token(*d)).collect();
+      let pos = coord(hazard.get_pos());
+      lines.push(match hazard.get_kind() {
+        HazardKind::Creature => format!("creature {}", pos),
+        HazardKind::Patrol   => format!("patrol {} {}", pos, path),
+        HazardKind::Rock     => format!("rock {} {}", pos, path)
+      });
+    }
+    for portal in &self.portals {
+      let [a, b] = portal.get_ends();
+      lines.push(format!("portal {} {}", coord(a), coord(b)));
+    }
+
+    lines.join("\n") + "\n"
+  }
+
+  pub fn get_cells(&self) -> impl Iterator<Item = Pos> + '_ {
+    self.bricks.iter()
+      .copied()
+      .chain(self.hazards.iter().map(|h| h.get_pos()))
+      .chain(self.portals.iter().flat_map(|p| p.get_ends()))
+  }
+
+  pub fn check_bounds(&self, field_size: Size) -> error::Result<()> {
+    for pos in self.get_cells() {
+      if pos.x > field_size.width + 1 || pos.y > field_size.height {
+        return Err(SnakeError::LevelBounds(pos.x - 2, pos.y - 1));
+      }
+    }
+
+    let cells = field_size.width as usize * field_size.height as usize;
+    let taken = self.get_cells().collect::<HashSet<_>>().len();
+    if cells <= taken + MAX_PLAYERS {
+      return Err(SnakeError::LevelFull);
+    }
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::hazard::HazardKind;
+  use crate::ui::dimensions::{Pos, Size};
+  use super::Level;
+
+  #[test]
+  fn test_level_parse() {
+    let level = Level::parse("
+      # тестовый уровень
+      name Коридор
+      brick 0 0
+      brick 3 4
+      patrol 5 5 RRLL
+      creature 10 2
+      rock 7 0 d
+      portal 1 1 20 10
+    ").unwrap();
+
+    assert_eq!(level.name, "Коридор");
+    assert_eq!(level.bricks.len(), 2);
+    assert!(level.bricks[0] == Pos::from((2, 1)));
+    assert_eq!(level.hazards.len(), 3);
+    assert_eq!(level.hazards[0].get_kind(), HazardKind::Patrol);
+    assert_eq!(level.hazards[1].get_kind(), HazardKind::Creature);
+    assert_eq!(level.hazards[2].get_kind(), HazardKind::Rock);
+    assert_eq!(level.portals.len(), 1);
+    assert!(level.portals[0].partner(&Pos::from((3, 2))) == Some(Pos::from((22, 11))));
+    assert_eq!(Level::parse(&level.encode()).unwrap().encode(), level.encode());
+    assert!(level.encode().contains("patrol 5 5 RRLL\n"));
+  }
+
+  #[test]
+  fn test_level_errors() {
+    assert!(Level::parse("wall 1 1").is_err());
+    assert!(Level::parse("brick 1").is_err());
+    assert!(Level::parse("patrol 1 1 RX").is_err());
+    assert!(Level::parse("rock 1 1 DD").is_err());
+    assert!(Level::parse("portal 1 1 2").is_err());
+    assert!(Level::parse("brick 65534 0").is_err());
+    assert!(Level::parse("brick 0 65535").is_err());
+  }
+
+  #[test]
+  fn test_level_bounds() {
+    let level = Level::parse("brick 9 9").unwrap();
+    assert!(level.check_bounds(Size::from((10, 10))).is_ok());
+    assert!(level.check_bounds(Size::from((9, 10))).is_err());
+
+    let full = (0..9).map(|i| format!("brick {} {}\n", i % 3, i / 3))
+      .collect::<String>();
+    let level = Level::parse(&full).unwrap();
+    assert!(level.check_bounds(Size::from((3, 3))).is_err());
+    assert!(level.check_bounds(Size::from((3, 5))).is_ok());
+  }
+}
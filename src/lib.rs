@@ -0,0 +1,47 @@
+//! Консольная змейка: правила игры, боты, сеть и терминальный интерфейс.
+
+pub mod ui;
+pub mod ai;
+mod bench;
+mod bot;
+pub mod env;
+pub mod food;
+pub mod hazard;
+pub mod i18n;
+mod json;
+pub mod level;
+mod net;
+pub mod portal;
+pub mod rng;
+pub mod save;
+pub mod score;
+pub mod settings;
+pub mod snake;
+mod snapshot;
+pub mod stats;
+pub mod clock;
+pub mod splits;
+mod storage;
+pub mod game;
+pub mod world;
+pub mod error;
+
+#[cfg(test)]
+mod testing;
+
+pub use bench::{Pilot, GameResult, Summary};
+pub use bot::BotProcess;
+pub use error::{SnakeError, Result};
+pub use food::{Food, FoodType};
+pub use json::{Object, ToJson};
+pub use net::{
+  DEFAULT_PORT,
+  broadcast::{Address, Broadcaster},
+  client::Client,
+  server::Server,
+  watch::Watcher
+};
+pub use snake::{Snake, SnakePart, Direction};
+pub use ui::{Drawable, renderer::Renderer};
+pub use ui::dimensions::{Pos, Size};
+pub use world::{World, GameOptions, Event, DeathCause, Outcome, Player, Field};
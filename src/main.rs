@@ -1,19 +1,352 @@
-pub mod ui;
-pub mod food;
-pub mod snake;
-pub mod game;
-pub mod error;
+mod cli;
 
-use ui::UI;
-use game::Game;
+use std::{
+  path::{Path, PathBuf},
+  time::{Duration, Instant}
+};
 
-fn main() {
-  let ui = match UI::new() {
+use rand::{Rng, SeedableRng};
+
+use snake::{
+  World, GameOptions, Size,
+  Pilot, GameResult, Summary, BotProcess, Object, ToJson,
+  Server, Client, Watcher, Address, Broadcaster
+};
+use snake::ui::{
+  UI, cast, theme,
+  menu::{self, Menu, MenuAction},
+  ui_items::PopupWindow
+};
+use snake::ai::Difficulty;
+use snake::env::{Env, Action, ACTIONS};
+use snake::error::SnakeError;
+use snake::i18n;
+use snake::game::{Game, Finish};
+use snake::rng::GameRng;
+use snake::save::{self, SavedGame};
+use snake::settings::Settings;
+use snake::stats::{self, Stats};
+use snake::tr;
+
+use cli::Command;
+
+const DEMO_IDLE: Duration = Duration::from_secs(20);
+
+fn start_ui(options: &GameOptions) -> UI {
+  let ui = match UI::new(options.players.max(1)) {
     Ok(ui) => ui,
     Err(err) => {
-      panic!("Не могу инициализировать интерфейс: {}", err)
+      panic!("{}", tr!("main.ui", err))
     }
   };
 
-  Game::new(ui).run();
-}
\ No newline at end of file
+  if let Some(level) = &options.level {
+    if let Err(err) = level.check_bounds(ui.field_size) {
+      ui.disable_raw_mode();
+      panic!("{}", tr!("main.level_bounds", err))
+    }
+  }
+
+  ui
+}
+
+fn with_broadcast(game: Game, broadcast: Option<&Broadcaster>) -> Game {
+  match broadcast {
+    Some(broadcaster) => game.broadcast(broadcaster.clone()),
+    None => game
+  }
+}
+
+fn play(options: GameOptions, settings: &mut Settings,
+    broadcast: Option<&Broadcaster>) -> Finish {
+
+  let game = Game::new(start_ui(&options), options).settings(*settings);
+  let mut game = with_broadcast(game, broadcast);
+  game.run();
+  *settings = game.get_settings();
+  game.get_finish()
+}
+
+fn resume(settings: &mut Settings, broadcast: Option<&Broadcaster>) -> Finish {
+  let saved = match SavedGame::load() {
+    Ok(saved) => saved,
+    Err(err) => panic!("{}", tr!("main.load", err))
+  };
+
+  let humans = saved.world.humans().max(1);
+  let ui = match UI::with_field_size(humans, saved.world.get_field_size()) {
+    Ok(ui) => ui,
+    Err(err) => panic!("{}", tr!("main.ui", err))
+  };
+
+  let game = Game::resume(ui, saved).settings(*settings);
+  let _ = save::remove();
+  let mut game = with_broadcast(game, broadcast);
+  game.run();
+  *settings = game.get_settings();
+  game.get_finish()
+}
+
+fn start_broadcast(address: &Address) -> Broadcaster {
+  match Broadcaster::bind(address) {
+    Ok(broadcaster) => broadcaster,
+    Err(err) => panic!("{}", tr!("main.broadcast", err))
+  }
+}
+
+fn start_recording(path: &Path, command: &Command) {
+  let size = match command {
+    Command::Bot { headless: true, field_size, .. } =>
+      Ok(UI::minimum_size(*field_size)),
+    _ => cast::size().map(Size::from)
+  };
+  let headless = matches!(command, Command::Bot { headless: true, .. });
+
+  if let Err(err) = size.and_then(|size| cast::start(path, size, headless)) {
+    panic!("{}", tr!("main.record", path.display(), err))
+  }
+}
+
+fn record_headless(bot: &mut BotProcess, world: &mut World) {
+  let mut ui = match UI::with_field_size(1, world.get_field_size()) {
+    Ok(ui) => ui,
+    Err(err) => panic!("{}", tr!("main.ui", err))
+  };
+
+  if let Err(err) = bot.play_recorded(world, &mut ui) {
+    panic!("{}", tr!("main.render", err))
+  }
+}
+
+fn bot(cmd: &str, timeout: Duration, headless: bool,
+    field_size: Size, options: GameOptions) {
+
+  let mut bot = match BotProcess::spawn(cmd, timeout, !headless) {
+    Ok(bot) => bot,
+    Err(err) => panic!("{}", tr!("main.bot", err))
+  };
+
+  if headless {
+    let mut world = World::new(field_size, &options);
+    if cast::is_headless() {
+      record_headless(&mut bot, &mut world);
+    }
+    else {
+      bot.play(&mut world);
+    }
+    println!("{}", GameResult::from_world(&world, 0).to_json());
+  }
+  else {
+    Game::new(start_ui(&options), options).pilot(bot).run();
+  }
+}
+
+fn demo(options: &GameOptions, settings: &mut Settings, kiosk: bool,
+    broadcast: Option<&Broadcaster>) {
+
+  let mut bots = vec![Difficulty::Cautious];
+  bots.extend(&options.bots);
+
+  let options = GameOptions {
+    players: 0,
+    bots,
+    demo: true,
+    ..options.clone()
+  };
+
+  while play(options.clone(), settings, broadcast) == Finish::Over && kiosk {}
+}
+
+fn env_throughput(episodes: u64, seed: u64,
+    field_size: Size, options: GameOptions) {
+
+  let mut env = Env::new(field_size, options);
+  let mut policy = GameRng::seed_from_u64(seed);
+  let (mut steps, mut total_reward, mut total_score) = (0u64, 0.0, 0u64);
+  let start = Instant::now();
+
+  for episode in 0..episodes {
+    env.reset(seed + episode);
+
+    loop {
+      let action = Action::try_from(policy.gen_range(0..ACTIONS)).unwrap();
+      let (_, reward, done, info) = env.step(action);
+      total_reward += reward;
+      steps += 1;
+
+      if done {
+        total_score += info.score;
+        break;
+      }
+    }
+  }
+
+  let seconds = start.elapsed().as_secs_f64();
+  println!("{}", Object::new()
+    .field("episodes", &episodes)
+    .field("steps", &steps)
+    .field("seconds", &seconds)
+    .field("episodes_per_second", &(episodes as f64 / seconds))
+    .field("mean_reward", &(total_reward / episodes.max(1) as f64))
+    .field("mean_score", &(total_score as f64 / episodes.max(1) as f64))
+    .to_json());
+}
+
+fn bench(pilot: &Pilot, games: u64, seed_start: u64, jobs: usize,
+    json: bool, field_size: Size, options: &GameOptions) {
+
+  let results = match pilot.bench(games, seed_start, jobs, field_size, options) {
+    Ok(results) => results,
+    Err(err) => panic!("{}", tr!("main.bench", err))
+  };
+
+  let summary = Summary::new(&results);
+  if json {
+    println!("{}", summary.to_json(&results));
+  }
+  else {
+    println!("{}", summary.table());
+  }
+}
+
+fn show_stats() {
+  let (session, lifetime) = (stats::session(), Stats::load());
+  let mut lines = stats::table(&[
+    (tr!("stats.session"), &session),
+    (tr!("stats.lifetime"), &lifetime)
+  ]);
+  lines.push(String::new());
+  lines.push(tr!("stats.hint").to_string());
+
+  let window = PopupWindow::new(format!(" {} ", tr!("stats.title")), lines);
+  if let Err(err) = menu::show(&window) {
+    panic!("{}", tr!("main.menu", err))
+  }
+}
+
+fn menu(options: GameOptions, settings: &mut Settings,
+    broadcast: Option<&Broadcaster>) {
+
+  const ITEMS: [&str; 7] = [
+    "menu.continue",
+    "menu.single",
+    "menu.two",
+    "menu.bot",
+    "menu.demo",
+    "menu.stats",
+    "menu.quit"
+  ];
+
+  loop {
+    let skip = if save::exists() { 0 } else { 1 };
+    let items: Vec<&str> = ITEMS[skip..].iter().map(|key| tr!(key)).collect();
+    let mut menu = Menu::new(tr!("menu.title"), &items);
+
+    let action = match menu.run(DEMO_IDLE) {
+      Ok(action) => action,
+      Err(err) => panic!("{}", tr!("main.menu", err))
+    };
+
+    match action {
+      MenuAction::Select(i) => match i + skip {
+        0 => { resume(settings, broadcast); },
+        1 => { play(options.clone(), settings, broadcast); },
+        2 => {
+          let options = GameOptions { players: 2, ..options.clone() };
+          play(options, settings, broadcast);
+        },
+        3 => {
+          play(GameOptions {
+            bots: vec![Difficulty::Cautious],
+            ..options.clone()
+          }, settings, broadcast);
+        },
+        4 => demo(&options, settings, false, broadcast),
+        5 => show_stats(),
+        _ => break
+      },
+      MenuAction::Idle => demo(&options, settings, false, broadcast),
+      MenuAction::Quit => break
+    }
+  }
+}
+
+fn main() {
+  let mut args: Vec<String> = std::env::args().skip(1).collect();
+  let lang = cli::take_option(&mut args, "--lang");
+  if let Err(err) = lang.and_then(|lang| i18n::init(lang.as_deref())) {
+    panic!("{}", err)
+  }
+
+  let record = match cli::take_option(&mut args, "--record") {
+    Ok(record) => record.map(PathBuf::from),
+    Err(err) => panic!("{}", err)
+  };
+
+  let command = match cli::parse(args) {
+    Ok(command) => command,
+    Err(err) => panic!("{}", err)
+  };
+
+  if record.is_some() && !command.renders() {
+    panic!("{}", SnakeError::Args(tr!("args.record").to_string()))
+  }
+
+  if let Some(path) = &record {
+    start_recording(path, &command);
+  }
+
+  let mut settings = Settings::load();
+  theme::set(settings.theme());
+
+  match command {
+    Command::Menu => menu(GameOptions::default(), &mut settings, None),
+    Command::Play(options, address) if options.demo => {
+      let broadcaster = address.as_ref().map(start_broadcast);
+      demo(&options, &mut settings, true, broadcaster.as_ref())
+    },
+    Command::Play(options, address) => {
+      let broadcaster = address.as_ref().map(start_broadcast);
+      let broadcast = broadcaster.as_ref();
+      if play(options.clone(), &mut settings, broadcast) == Finish::Menu {
+        menu(options, &mut settings, broadcast);
+      }
+    },
+    Command::Resume(address) => {
+      let broadcaster = address.as_ref().map(start_broadcast);
+      let broadcast = broadcaster.as_ref();
+      if resume(&mut settings, broadcast) == Finish::Menu {
+        menu(GameOptions::default(), &mut settings, broadcast);
+      }
+    },
+    Command::Stats(format) => println!("{}", Stats::load().export(format)),
+    Command::Serve { bind, port, field_size, options } => {
+      if let Err(err) = Server::new(field_size, &options).run(&bind, port) {
+        panic!("{}", tr!("main.server", err))
+      }
+    },
+    Command::Bench {
+      pilot, games, seed_start, jobs, json, field_size, options
+    } => bench(&pilot, games, seed_start, jobs, json, field_size, &options),
+    Command::Env { episodes, seed, field_size, options } =>
+      env_throughput(episodes, seed, field_size, options),
+    Command::Bot { cmd, timeout, headless, field_size, options } =>
+      bot(&cmd, timeout, headless, field_size, options),
+    Command::Join(address) => {
+      if let Err(err) = Client::join(&address) {
+        panic!("{}", tr!("main.join", address, err))
+      }
+    },
+    Command::Watch(address) => {
+      if let Err(err) = Watcher::watch(&address) {
+        panic!("{}", tr!("main.watch", err))
+      }
+    }
+  }
+
+  if let Some(path) = &record {
+    if let Err(err) = cast::finish() {
+      panic!("{}", tr!("main.record", path.display(), err))
+    }
+  }
+}
@@ -0,0 +1,165 @@
+pub mod server;
+pub mod client;
+pub mod broadcast;
+pub mod view;
+pub mod watch;
+
+use crate::snake::Direction;
+use crate::snapshot::{Snapshot, dir_token};
+use crate::ui::dimensions::Size;
+use crate::world::Outcome;
+
+pub const DEFAULT_PORT: u16 = 4517;
+
+#[derive(PartialEq, Debug)]
+pub enum ServerMessage {
+  Welcome { slot: usize, token: u64, field_size: Size, players: usize },
+  Watch { field_size: Size, players: usize },
+  Full,
+  Lobby { connected: usize, needed: usize },
+  Countdown(u64),
+  State(Snapshot),
+  Over(Outcome)
+}
+
+#[derive(PartialEq, Debug)]
+pub enum ClientMessage {
+  Hello(Option<(usize, u64)>),
+  Turn(Direction),
+  Boost,
+  Quit
+}
+
+impl ServerMessage {
+  pub fn encode(&self) -> String {
+    match self {
+      ServerMessage::Welcome { slot, token, field_size, players } =>
+        format!(
+          "WELCOME {} {} {} {} {}",
+          slot, token, field_size.width, field_size.height, players
+        ),
+      ServerMessage::Watch { field_size, players } => format!(
+        "WATCH {} {} {}", field_size.width, field_size.height, players
+      ),
+      ServerMessage::Full => "FULL".to_string(),
+      ServerMessage::Lobby { connected, needed } =>
+        format!("LOBBY {} {}", connected, needed),
+      ServerMessage::Countdown(n) => format!("COUNTDOWN {}", n),
+      ServerMessage::State(snapshot) =>
+        format!("STATE {}", snapshot.encode()),
+      ServerMessage::Over(Outcome::Winner(i)) => format!("OVER {}", i),
+      ServerMessage::Over(Outcome::Draw) => "OVER -".to_string()
+    }
+  }
+
+  pub fn decode(line: &str) -> Option<Self> {
+    let line = line.trim();
+    let (command, rest) = line.split_once(' ').unwrap_or((line, ""));
+    let words: Vec<&str> = rest.split_whitespace().collect();
+    let num = |i: usize| words.get(i)?.parse::<u64>().ok();
+
+    let message = match command {
+      "WELCOME" => ServerMessage::Welcome {
+        slot: num(0)? as usize,
+        token: num(1)?,
+        field_size: Size::from((num(2)? as u16, num(3)? as u16)),
+        players: num(4)? as usize
+      },
+      "WATCH" => ServerMessage::Watch {
+        field_size: Size::from((num(0)? as u16, num(1)? as u16)),
+        players: num(2)? as usize
+      },
+      "FULL" => ServerMessage::Full,
+      "LOBBY" => ServerMessage::Lobby {
+        connected: num(0)? as usize,
+        needed: num(1)? as usize
+      },
+      "COUNTDOWN" => ServerMessage::Countdown(num(0)?),
+      "STATE" => ServerMessage::State(Snapshot::decode(rest)?),
+      "OVER" => match *words.first()? {
+        "-" => ServerMessage::Over(Outcome::Draw),
+        _ => ServerMessage::Over(Outcome::Winner(num(0)? as usize))
+      },
+      _ => return None
+    };
+
+    Some(message)
+  }
+}
+
+impl ClientMessage {
+  pub fn encode(&self) -> String {
+    match self {
+      ClientMessage::Hello(None) => "HELLO".to_string(),
+      ClientMessage::Hello(Some((slot, token))) =>
+        format!("HELLO {} {}", slot, token),
+      ClientMessage::Turn(dir) => format!("TURN {}", dir_token(*dir)),
+      ClientMessage::Boost => "BOOST".to_string(),
+      ClientMessage::Quit => "QUIT".to_string()
+    }
+  }
+
+  pub fn decode(line: &str) -> Option<Self> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+
+    let message = match *words.first()? {
+      "HELLO" if words.len() == 3 => ClientMessage::Hello(Some((
+        words[1].parse().ok()?,
+        words[2].parse().ok()?
+      ))),
+      "HELLO" => ClientMessage::Hello(None),
+      "TURN" => ClientMessage::Turn(
+        Direction::try_from(words.get(1)?.chars().next()?).ok()?
+      ),
+      "BOOST" => ClientMessage::Boost,
+      "QUIT" => ClientMessage::Quit,
+      _ => return None
+    };
+
+    Some(message)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::snake::Direction;
+  use crate::ui::dimensions::Size;
+  use crate::world::Outcome;
+  use super::{ServerMessage, ClientMessage};
+
+  #[test]
+  fn test_server_message_roundtrip() {
+    let messages = [
+      ServerMessage::Welcome {
+        slot: 1, token: 123456789, field_size: Size::from((40, 20)), players: 2
+      },
+      ServerMessage::Watch { field_size: Size::from((40, 20)), players: 1 },
+      ServerMessage::Full,
+      ServerMessage::Lobby { connected: 1, needed: 2 },
+      ServerMessage::Countdown(3),
+      ServerMessage::Over(Outcome::Winner(1)),
+      ServerMessage::Over(Outcome::Draw)
+    ];
+
+    for message in messages {
+      assert_eq!(ServerMessage::decode(&message.encode()), Some(message));
+    }
+    assert_eq!(ServerMessage::decode("HELLO"), None);
+  }
+
+  #[test]
+  fn test_client_message_roundtrip() {
+    let messages = [
+      ClientMessage::Hello(None),
+      ClientMessage::Hello(Some((1, 42))),
+      ClientMessage::Turn(Direction::Left),
+      ClientMessage::Boost,
+      ClientMessage::Quit
+    ];
+
+    for message in messages {
+      assert_eq!(ClientMessage::decode(&message.encode()), Some(message));
+    }
+    assert_eq!(ClientMessage::decode("TURN X"), None);
+  }
+}
@@ -0,0 +1,311 @@
+use std::{
+  io::{self, Read, Write},
+  net::{Shutdown, TcpListener, TcpStream},
+  path::PathBuf,
+  thread,
+  sync::{
+    Arc, Mutex, Weak,
+    mpsc::{self, SyncSender}
+  }
+};
+
+#[cfg(unix)]
+use std::os::unix::{
+  fs::FileTypeExt,
+  net::{UnixListener, UnixStream}
+};
+
+use crate::error::{self, SnakeError};
+use crate::net::ServerMessage;
+use crate::tr;
+
+const OUTBOX_LINES: usize = 64;
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum Address {
+  Tcp(String),
+  Unix(PathBuf)
+}
+
+impl Address {
+  pub fn parse(addr: &str) -> error::Result<Self> {
+    match addr.split_once(':') {
+      Some(("unix", path)) if cfg!(unix) && !path.is_empty() =>
+        Ok(Address::Unix(PathBuf::from(path))),
+      Some(("unix", _)) =>
+        Err(SnakeError::Args(tr!("args.unix_socket").to_string())),
+      Some(("tcp", addr)) => Ok(Address::Tcp(addr.to_string())),
+      Some(_) => Ok(Address::Tcp(addr.to_string())),
+      None => Err(SnakeError::Args(tr!("args.address", addr)))
+    }
+  }
+}
+
+pub enum Stream {
+  Tcp(TcpStream),
+  #[cfg(unix)]
+  Unix(UnixStream)
+}
+
+impl Stream {
+  pub fn connect(address: &Address) -> io::Result<Self> {
+    match address {
+      Address::Tcp(addr) => TcpStream::connect(addr).map(Stream::Tcp),
+      #[cfg(unix)]
+      Address::Unix(path) => UnixStream::connect(path).map(Stream::Unix),
+      #[cfg(not(unix))]
+      Address::Unix(_) => Err(io::ErrorKind::Unsupported.into())
+    }
+  }
+
+  pub fn try_clone(&self) -> io::Result<Self> {
+    match self {
+      Stream::Tcp(stream) => stream.try_clone().map(Stream::Tcp),
+      #[cfg(unix)]
+      Stream::Unix(stream) => stream.try_clone().map(Stream::Unix)
+    }
+  }
+
+  pub fn shutdown(&self) -> io::Result<()> {
+    match self {
+      Stream::Tcp(stream) => stream.shutdown(Shutdown::Both),
+      #[cfg(unix)]
+      Stream::Unix(stream) => stream.shutdown(Shutdown::Both)
+    }
+  }
+}
+
+impl Read for Stream {
+  fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+    match self {
+      Stream::Tcp(stream) => stream.read(buf),
+      #[cfg(unix)]
+      Stream::Unix(stream) => stream.read(buf)
+    }
+  }
+}
+
+impl Write for Stream {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    match self {
+      Stream::Tcp(stream) => stream.write(buf),
+      #[cfg(unix)]
+      Stream::Unix(stream) => stream.write(buf)
+    }
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    match self {
+      Stream::Tcp(stream) => stream.flush(),
+      #[cfg(unix)]
+      Stream::Unix(stream) => stream.flush()
+    }
+  }
+}
+
+enum Listener {
+  Tcp(TcpListener),
+  #[cfg(unix)]
+  Unix(UnixListener)
+}
+
+impl Listener {
+  fn bind(address: &Address) -> io::Result<Self> {
+    match address {
+      Address::Tcp(addr) => TcpListener::bind(addr).map(Listener::Tcp),
+      #[cfg(unix)]
+      Address::Unix(path) => {
+        match std::fs::symlink_metadata(path) {
+          Ok(meta) if !meta.file_type().is_socket() =>
+            return Err(io::ErrorKind::AlreadyExists.into()),
+          Ok(_) if UnixStream::connect(path).is_err() =>
+            std::fs::remove_file(path)?,
+          _ => ()
+        }
+        UnixListener::bind(path).map(Listener::Unix)
+      },
+      #[cfg(not(unix))]
+      Address::Unix(_) => Err(io::ErrorKind::Unsupported.into())
+    }
+  }
+
+  fn accept(&self) -> io::Result<Stream> {
+    match self {
+      Listener::Tcp(listener) => listener.accept().map(|(s, _)| Stream::Tcp(s)),
+      #[cfg(unix)]
+      Listener::Unix(listener) =>
+        listener.accept().map(|(s, _)| Stream::Unix(s))
+    }
+  }
+}
+
+pub struct Outbox {
+  sender: SyncSender<String>,
+  stream: Stream
+}
+
+impl Outbox {
+  pub fn new(stream: Stream) -> io::Result<Self> {
+    let mut writer = stream.try_clone()?;
+    let (sender, receiver) = mpsc::sync_channel::<String>(OUTBOX_LINES);
+
+    thread::spawn(move || {
+      for line in receiver {
+        if writeln!(writer, "{}", line).is_err() {
+          break;
+        }
+      }
+      let _ = writer.shutdown();
+    });
+
+    Ok(Self { sender, stream })
+  }
+
+  pub fn send(&self, line: &str) -> bool {
+    self.sender.try_send(line.to_string()).is_ok()
+  }
+}
+
+impl Drop for Outbox {
+  fn drop(&mut self) {
+    let _ = self.stream.shutdown();
+  }
+}
+
+struct Shared {
+  watchers: Mutex<Vec<Outbox>>,
+  header: Mutex<Option<String>>,
+  last: Mutex<Option<String>>,
+  socket: Option<PathBuf>
+}
+
+impl Drop for Shared {
+  fn drop(&mut self) {
+    if let Some(path) = &self.socket {
+      let _ = std::fs::remove_file(path);
+    }
+  }
+}
+
+#[derive(Clone)]
+pub struct Broadcaster {
+  shared: Arc<Shared>
+}
+
+impl Broadcaster {
+  pub fn bind(address: &Address) -> io::Result<Self> {
+    let listener = Listener::bind(address)?;
+    let socket = match address {
+      Address::Unix(path) => Some(path.clone()),
+      Address::Tcp(_) => None
+    };
+
+    let shared = Arc::new(Shared {
+      watchers: Mutex::new(Vec::new()),
+      header: Mutex::new(None),
+      last: Mutex::new(None),
+      socket
+    });
+
+    let weak = Arc::downgrade(&shared);
+    thread::spawn(move || Self::accept(listener, weak));
+
+    Ok(Self { shared })
+  }
+
+  fn accept(listener: Listener, shared: Weak<Shared>) {
+    while let Ok(stream) = listener.accept() {
+      let Some(shared) = shared.upgrade() else {
+        break;
+      };
+      let Ok(watcher) = Outbox::new(stream) else {
+        continue;
+      };
+
+      let header = shared.header.lock().unwrap().clone();
+      let last = shared.last.lock().unwrap().clone();
+      if header.iter().chain(&last).all(|line| watcher.send(line)) {
+        shared.watchers.lock().unwrap().push(watcher);
+      }
+    }
+  }
+
+  pub fn announce(&self, message: &ServerMessage) {
+    *self.shared.header.lock().unwrap() = Some(message.encode());
+    self.send(message.encode());
+  }
+
+  pub fn publish(&self, message: &ServerMessage) {
+    let line = message.encode();
+    *self.shared.last.lock().unwrap() = Some(line.clone());
+    self.send(line);
+  }
+
+  pub fn watchers(&self) -> usize {
+    self.shared.watchers.lock().unwrap().len()
+  }
+
+  fn send(&self, line: String) {
+    self.shared.watchers.lock().unwrap()
+      .retain(|watcher| watcher.send(&line));
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::io::{BufRead, BufReader};
+  use std::{thread::sleep, time::Duration};
+
+  use crate::net::ServerMessage;
+  use crate::ui::dimensions::Size;
+  use super::{Address, Broadcaster, Stream};
+
+  #[test]
+  fn test_watchers_get_header_and_updates() {
+    assert_eq!(Address::parse("localhost:9000").unwrap(),
+      Address::Tcp("localhost:9000".to_string()));
+    assert_eq!(Address::parse("tcp:[::1]:9000").unwrap(),
+      Address::Tcp("[::1]:9000".to_string()));
+    assert!(Address::parse("snake.sock").is_err());
+
+    let file = std::env::temp_dir().join("snake-broadcast-test.txt");
+    std::fs::write(&file, "notes").unwrap();
+    assert!(Broadcaster::bind(&Address::Unix(file.clone())).is_err());
+    assert_eq!(std::fs::read_to_string(&file).unwrap(), "notes");
+    std::fs::remove_file(&file).unwrap();
+
+    let probe = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = probe.local_addr().unwrap().port();
+    drop(probe);
+
+    let address = Address::Tcp(format!("127.0.0.1:{}", port));
+    let broadcaster = Broadcaster::bind(&address).unwrap();
+    broadcaster.announce(&ServerMessage::Watch {
+      field_size: Size::from((20, 10)), players: 2
+    });
+    broadcaster.publish(&ServerMessage::Countdown(3));
+
+    let watchers: Vec<_> = (0..2)
+      .map(|_| BufReader::new(Stream::connect(&address).unwrap()))
+      .collect();
+    while broadcaster.watchers() < 2 {
+      sleep(Duration::from_millis(10));
+    }
+    broadcaster.publish(&ServerMessage::Countdown(2));
+
+    for mut watcher in watchers {
+      let lines: Vec<_> = (0..3)
+        .map(|_| {
+          let mut line = String::new();
+          watcher.read_line(&mut line).unwrap();
+          ServerMessage::decode(&line).unwrap()
+        })
+        .collect();
+      assert_eq!(lines, [
+        ServerMessage::Watch { field_size: Size::from((20, 10)), players: 2 },
+        ServerMessage::Countdown(3),
+        ServerMessage::Countdown(2)
+      ]);
+    }
+  }
+}
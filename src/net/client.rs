@@ -0,0 +1,190 @@
+use std::{
+  io::{BufRead, BufReader, Write},
+  net::{Shutdown, TcpStream},
+  thread::{sleep, self},
+  time::Duration,
+  sync::{
+    Arc, Mutex,
+    atomic::{AtomicBool, Ordering}
+  }
+};
+
+use crate::error::{self, SnakeError};
+use crate::game::game_action::{KeyAction, KeyController};
+use crate::net::{ServerMessage, ClientMessage, view::View};
+use crate::snake::Direction;
+use crate::settings::{Settings, KeyScheme};
+
+use crate::tr;
+use crate::ui::dimensions::Size;
+
+use crate::world::Outcome;
+
+const RECONNECT_ATTEMPTS: usize = 10;
+
+struct Session {
+  reader: BufReader<TcpStream>,
+  stream: TcpStream,
+  slot: usize,
+  token: u64,
+  field_size: Size,
+  players: usize
+}
+
+fn connect(addr: &str, resume: Option<(usize, u64)>) -> error::Result<Session> {
+  let mut stream = TcpStream::connect(addr)?;
+  writeln!(stream, "{}", ClientMessage::Hello(resume).encode())?;
+
+  let mut reader = BufReader::new(stream.try_clone()?);
+  let mut line = String::new();
+  reader.read_line(&mut line)?;
+
+  match ServerMessage::decode(&line) {
+    Some(ServerMessage::Welcome { slot, token, field_size, players }) =>
+      Ok(Session { reader, stream, slot, token, field_size, players }),
+    Some(ServerMessage::Full) => Err(SnakeError::Network(
+      tr!("net.full").to_string()
+    )),
+    _ => Err(SnakeError::Network(
+      tr!("net.unexpected").to_string()
+    ))
+  }
+}
+
+pub struct Client {
+  addr: String,
+  view: View,
+  session: Session,
+  writer: Arc<Mutex<TcpStream>>,
+  stop: Arc<AtomicBool>
+}
+
+impl Client {
+  pub fn join(addr: &str) -> error::Result<()> {
+    let session = connect(addr, None)?;
+    let view = View::new(session.field_size, session.players)?;
+
+    let mut client = Client {
+      addr: addr.to_string(),
+      view,
+      writer: Arc::new(Mutex::new(session.stream.try_clone()?)),
+      session,
+      stop: Arc::new(AtomicBool::new(false))
+    };
+
+    client.view.ui.print_popup_message(
+      &tr!("net.you_are", client.session.slot + 1)
+    )?;
+    client.spawn_input();
+
+    let result = client.run();
+    client.view.ui.disable_raw_mode();
+    result
+  }
+
+  fn spawn_input(&self) {
+    let writer = self.writer.clone();
+    let stop = self.stop.clone();
+
+    thread::spawn(move || -> std::io::Result<()> {
+      let layout = Settings::load().layout;
+      let key_controller = KeyController::new(1, KeyScheme::Wasd, layout);
+
+      loop {
+        let message = match key_controller.fetch_action()?.1 {
+          KeyAction::MoveUp    => ClientMessage::Turn(Direction::Up),
+          KeyAction::MoveDown  => ClientMessage::Turn(Direction::Down),
+          KeyAction::MoveLeft  => ClientMessage::Turn(Direction::Left),
+          KeyAction::MoveRight => ClientMessage::Turn(Direction::Right),
+          KeyAction::Boost     => ClientMessage::Boost,
+          KeyAction::Exit      => ClientMessage::Quit,
+          _ => continue
+        };
+
+        let mut writer = writer.lock().unwrap();
+        let _ = writeln!(writer, "{}", message.encode());
+
+        if message == ClientMessage::Quit {
+          stop.store(true, Ordering::Release);
+          let _ = writer.shutdown(Shutdown::Both);
+          break;
+        }
+      }
+
+      Ok(())
+    });
+  }
+
+  fn run(&mut self) -> error::Result<()> {
+    loop {
+      let mut line = String::new();
+      let read = self.session.reader.read_line(&mut line);
+
+      if self.stop.load(Ordering::Acquire) {
+        return Ok(());
+      }
+
+      if !matches!(read, Ok(n) if n > 0) {
+        self.reconnect()?;
+        continue;
+      }
+
+      match ServerMessage::decode(&line) {
+        Some(ServerMessage::Lobby { connected, needed }) => {
+          self.view.ui.print_popup_message(
+            &tr!("net.waiting", connected, needed)
+          )?;
+        },
+        Some(ServerMessage::Countdown(n)) => {
+          self.view.ui.clear_popup_message()?;
+          self.view.ui.print_popup_message(&tr!("game.countdown", n))?;
+        },
+        Some(ServerMessage::State(snapshot)) => {
+          if self.view.get_last().is_none() {
+            self.view.ui.clear_popup_message()?;
+          }
+          self.view.render(snapshot)?;
+        },
+        Some(ServerMessage::Over(outcome)) => {
+          self.game_over(outcome)?;
+          return Ok(());
+        },
+        _ => ()
+      }
+    }
+  }
+
+  fn reconnect(&mut self) -> error::Result<()> {
+    let resume = Some((self.session.slot, self.session.token));
+    self.view.ui.print_popup_message(tr!("net.no_connection"))?;
+
+    for _ in 0..RECONNECT_ATTEMPTS {
+      sleep(Duration::from_secs(1));
+
+      if let Ok(session) = connect(&self.addr, resume) {
+        *self.writer.lock().unwrap() = session.stream.try_clone()?;
+        self.session = session;
+        self.view.ui.clear_popup_message()?;
+        return Ok(());
+      }
+    }
+
+    Err(SnakeError::Network(tr!("net.lost").to_string()))
+  }
+
+  fn game_over(&mut self, outcome: Outcome) -> error::Result<()> {
+    let message = match outcome {
+      Outcome::Winner(i) if i == self.session.slot => tr!("net.you_won").to_string(),
+      Outcome::Winner(i) => match self.view.get_last() {
+        Some(last) => last.winner_message(i),
+        None => tr!("game.player_won", i + 1)
+      },
+      Outcome::Draw => tr!("game.draw").to_string()
+    };
+
+    self.view.game_over(&message)?;
+    sleep(Duration::from_secs(3));
+
+    Ok(())
+  }
+}
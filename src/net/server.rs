@@ -0,0 +1,218 @@
+use std::{
+  io::{BufRead, BufReader, Result, Write},
+  net::{TcpListener, TcpStream},
+  thread::{sleep, self},
+  time::Duration,
+  sync::{Arc, Mutex}
+};
+
+use rand::Rng;
+
+use crate::net::{
+  ServerMessage, ClientMessage,
+  broadcast::{Outbox, Stream}
+};
+use crate::snapshot::Snapshot;
+use crate::tr;
+use crate::ui::dimensions::Size;
+use crate::world::{World, GameOptions, TICK_MS};
+
+const IDLE_TICKS: u64 = 30_000 / TICK_MS;
+
+struct Slot {
+  stream: Option<Outbox>,
+  connection: u64,
+  token: u64
+}
+
+#[derive(Clone)]
+pub struct Server {
+  world: Arc<Mutex<World>>,
+  slots: Arc<Mutex<Vec<Slot>>>,
+  connections: Arc<Mutex<u64>>,
+  field_size: Size
+}
+
+impl Server {
+  pub fn new(field_size: Size, options: &GameOptions) -> Self {
+    let world = World::new(field_size, options);
+    let mut rng = rand::thread_rng();
+    let slots = (0..world.humans())
+      .map(|_| Slot { stream: None, connection: 0, token: rng.gen() })
+      .collect();
+
+    Self {
+      world: Arc::new(Mutex::new(world)),
+      slots: Arc::new(Mutex::new(slots)),
+      connections: Arc::new(Mutex::new(0)),
+      field_size
+    }
+  }
+
+  pub fn run(&self, bind: &str, port: u16) -> Result<()> {
+    let listener = TcpListener::bind((bind, port))?;
+    println!("{}", tr!("server.waiting", listener.local_addr()?));
+
+    let server = self.clone();
+    thread::spawn(move || server.accept(listener));
+
+    self.lobby();
+    self.countdown();
+    self.game_loop();
+
+    Ok(())
+  }
+
+  fn accept(&self, listener: TcpListener) {
+    for stream in listener.incoming().flatten() {
+      let server = self.clone();
+      thread::spawn(move || server.handle(stream));
+    }
+  }
+
+  fn handle(&self, stream: TcpStream) -> Result<()> {
+    let peer = stream.peer_addr()?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream.try_clone()?;
+
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    stream.set_read_timeout(None)?;
+
+    let resume = match ClientMessage::decode(&line) {
+      Some(ClientMessage::Hello(resume)) => resume,
+      _ => return Ok(())
+    };
+
+    let (slot, connection) = {
+      let mut slots = self.slots.lock().unwrap();
+      let slot = match resume {
+        Some((slot, token)) if slots.get(slot)
+          .is_some_and(|s| s.token == token && s.stream.is_none()) => Some(slot),
+        _ => slots.iter().position(|s| s.stream.is_none())
+      };
+
+      let Some(slot) = slot else {
+        drop(slots);
+        writeln!(writer, "{}", ServerMessage::Full.encode())?;
+        return Ok(());
+      };
+
+      let mut connections = self.connections.lock().unwrap();
+      *connections += 1;
+
+      let outbox = Outbox::new(Stream::Tcp(stream.try_clone()?))?;
+      outbox.send(&ServerMessage::Welcome {
+        slot,
+        token: slots[slot].token,
+        field_size: self.field_size,
+        players: slots.len()
+      }.encode());
+
+      slots[slot].stream = Some(outbox);
+      slots[slot].connection = *connections;
+      (slot, *connections)
+    };
+
+    println!("{}", tr!("server.connected", slot + 1, peer));
+
+    for line in reader.lines() {
+      let Ok(line) = line else { break };
+
+      match ClientMessage::decode(&line) {
+        Some(ClientMessage::Turn(dir)) => {
+          self.world.lock().unwrap().players[slot].push_direction(dir);
+        },
+        Some(ClientMessage::Boost) => {
+          self.world.lock().unwrap().players[slot].toggle_boost();
+        },
+        Some(ClientMessage::Quit) => break,
+        _ => ()
+      }
+    }
+
+    let mut slots = self.slots.lock().unwrap();
+    if slots[slot].connection == connection {
+      slots[slot].stream = None;
+      println!("{}", tr!("server.disconnected", slot + 1));
+    }
+
+    Ok(())
+  }
+
+  fn connected(&self) -> usize {
+    self.slots.lock().unwrap().iter()
+      .filter(|s| s.stream.is_some())
+      .count()
+  }
+
+  fn broadcast(&self, message: &ServerMessage) {
+    let line = message.encode();
+
+    for slot in self.slots.lock().unwrap().iter_mut() {
+      if slot.stream.as_ref().is_some_and(|outbox| !outbox.send(&line)) {
+        slot.stream = None;
+      }
+    }
+  }
+
+  fn lobby(&self) {
+    let needed = self.slots.lock().unwrap().len();
+
+    loop {
+      let connected = self.connected();
+      self.broadcast(&ServerMessage::Lobby { connected, needed });
+
+      if connected == needed {
+        break;
+      }
+      sleep(Duration::from_millis(500));
+    }
+  }
+
+  fn countdown(&self) {
+    println!("{}", tr!("server.countdown"));
+
+    for n in (1..=3).rev() {
+      self.broadcast(&ServerMessage::Countdown(n));
+      sleep(Duration::from_secs(1));
+    }
+  }
+
+  fn game_loop(&self) {
+    let mut idle = 0;
+
+    loop {
+      sleep(Duration::from_millis(TICK_MS));
+
+      let (snapshot, outcome) = {
+        let mut world = self.world.lock().unwrap();
+        world.tick();
+
+        let outcome = if world.is_over() {
+          Some(world.outcome())
+        } else {
+          None
+        };
+        (Snapshot::from(&*world), outcome)
+      };
+
+      self.broadcast(&ServerMessage::State(snapshot));
+
+      if let Some(outcome) = outcome {
+        self.broadcast(&ServerMessage::Over(outcome));
+        println!("{}", tr!("server.over"));
+        break;
+      }
+
+      idle = if self.connected() == 0 { idle + 1 } else { 0 };
+      if idle > IDLE_TICKS {
+        println!("{}", tr!("server.abandoned"));
+        break;
+      }
+    }
+
+    sleep(Duration::from_secs(1));
+  }
+}
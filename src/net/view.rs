@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+
+use crate::error::{self, SnakeError};
+use crate::snapshot::Snapshot;
+use crate::tr;
+use crate::ui::{
+  UI, Drawable,
+  dimensions::{Pos, Size},
+  theme,
+  ui_items::Symbol
+};
+use crate::world::TICK_MS;
+
+pub struct View {
+  pub ui: UI,
+  field_size: Size,
+  frame: HashMap<Pos, Symbol>,
+  last: Option<Snapshot>
+}
+
+impl View {
+  pub fn new(field_size: Size, players: usize) -> error::Result<Self> {
+    let mut ui = UI::with_field_size(1, field_size)?;
+    let theme = theme::current();
+    let colors: Vec<_> = (0..players)
+      .map(|i| theme.player_colors(i).0)
+      .collect();
+    ui.set_players(&colors);
+
+    Ok(Self { ui, field_size, frame: HashMap::new(), last: None })
+  }
+
+  pub fn get_last(&self) -> Option<&Snapshot> {
+    self.last.as_ref()
+  }
+
+  pub fn clear(&mut self) -> error::Result<()> {
+    self.ui.clear_popup_message()?;
+    self.ui.clear_field()?;
+    self.frame.clear();
+    self.last = None;
+
+    Ok(())
+  }
+
+  pub fn render(&mut self, snapshot: Snapshot) -> error::Result<()> {
+    if !snapshot.fits(self.field_size) {
+      return Err(SnakeError::Network(tr!("net.unexpected").to_string()));
+    }
+
+    let frame: HashMap<Pos, Symbol> = snapshot
+      .symbols(self.field_size)
+      .into_iter()
+      .map(|symbol| (symbol.pos, symbol))
+      .collect();
+
+    for pos in self.frame.keys() {
+      if !frame.contains_key(pos) {
+        Symbol::new(*pos).draw()?;
+      }
+    }
+
+    for (pos, symbol) in &frame {
+      if self.frame.get(pos) != Some(symbol) {
+        symbol.draw()?;
+      }
+    }
+
+    for (i, player) in snapshot.players.iter().enumerate() {
+      let changed = self.last.as_ref()
+        .and_then(|last| last.players.get(i))
+        .is_none_or(|last| last.score != player.score ||
+          last.parts.len() != player.parts.len() ||
+          last.multiplier != player.multiplier);
+
+      if changed {
+        self.ui.print_stats(
+          i, &player.score, &(player.parts.len() as u16 - 1)
+        )?;
+        self.ui.print_combo(i, &player.multiplier)?;
+      }
+    }
+
+    if snapshot.tick.is_multiple_of(2) {
+      self.ui.print_time(&(snapshot.tick as f64 * TICK_MS as f64 / 1000.0))?;
+    }
+
+    self.frame = frame;
+    self.last = Some(snapshot);
+
+    Ok(())
+  }
+
+  pub fn game_over(&mut self, message: &str) -> error::Result<()> {
+    let rows: Vec<_> = self.last.iter()
+      .flat_map(|last| last.players.iter().enumerate()
+        .map(|(i, p)| (last.player_name(i), p.score)))
+      .collect();
+
+    self.ui.print_popup_message(message)?;
+    self.ui.print_game_over(&rows, false)?;
+
+    Ok(())
+  }
+}
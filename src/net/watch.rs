@@ -0,0 +1,134 @@
+use std::{
+  io::{BufRead, BufReader},
+  thread::{sleep, self},
+  time::Duration,
+  sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering}
+  }
+};
+
+use crate::error::{self, SnakeError};
+use crate::game::game_action::{KeyAction, KeyController};
+use crate::net::{
+  ServerMessage,
+  broadcast::{Address, Stream},
+  view::View
+};
+use crate::settings::{Settings, KeyScheme};
+use crate::tr;
+use crate::world::Outcome;
+
+pub struct Watcher {
+  view: View,
+  reader: BufReader<Stream>,
+  stop: Arc<AtomicBool>,
+  over: bool
+}
+
+impl Watcher {
+  pub fn watch(address: &Address) -> error::Result<()> {
+    let stream = Stream::connect(address)?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+
+    let Some(ServerMessage::Watch { field_size, players }) =
+        ServerMessage::decode(&line) else {
+      return Err(SnakeError::Network(tr!("net.unexpected").to_string()));
+    };
+
+    let mut watcher = Watcher {
+      view: View::new(field_size, players)?,
+      reader,
+      stop: Arc::new(AtomicBool::new(false)),
+      over: false
+    };
+
+    watcher.view.ui.print_popup_message(tr!("net.watching"))?;
+    watcher.spawn_input(stream);
+
+    let result = watcher.run();
+    watcher.view.ui.disable_raw_mode();
+    result
+  }
+
+  fn spawn_input(&self, stream: Stream) {
+    let stop = self.stop.clone();
+
+    thread::spawn(move || -> std::io::Result<()> {
+      let layout = Settings::load().layout;
+      let key_controller = KeyController::new(1, KeyScheme::Wasd, layout);
+
+      while key_controller.fetch_action()?.1 != KeyAction::Exit {}
+
+      stop.store(true, Ordering::Release);
+      stream.shutdown()
+    });
+  }
+
+  fn run(&mut self) -> error::Result<()> {
+    loop {
+      let mut line = String::new();
+      let read = self.reader.read_line(&mut line);
+
+      if self.stop.load(Ordering::Acquire) {
+        return Ok(());
+      }
+
+      if !matches!(read, Ok(n) if n > 0) {
+        self.view.ui.clear_popup_message()?;
+        self.view.ui.print_popup_message(tr!("net.broadcast_ended"))?;
+        sleep(Duration::from_secs(2));
+        return Ok(());
+      }
+
+      match ServerMessage::decode(&line) {
+        Some(ServerMessage::Watch { .. }) => self.view.clear()?,
+        Some(ServerMessage::Countdown(n)) => {
+          self.next_round()?;
+          self.view.ui.clear_popup_message()?;
+          self.view.ui.print_popup_message(&tr!("game.countdown", n))?;
+        },
+        Some(ServerMessage::State(snapshot)) => {
+          self.next_round()?;
+          if self.view.get_last().is_none() {
+            self.view.ui.clear_popup_message()?;
+          }
+          self.view.render(snapshot)?;
+        },
+        Some(ServerMessage::Over(outcome)) => {
+          self.game_over(outcome)?;
+          self.over = true;
+        },
+        _ => ()
+      }
+    }
+  }
+
+  fn next_round(&mut self) -> error::Result<()> {
+    if self.over {
+      self.over = false;
+      self.view.clear()?;
+    }
+
+    Ok(())
+  }
+
+  fn game_over(&mut self, outcome: Outcome) -> error::Result<()> {
+    let players = self.view.get_last().map_or(0, |last| last.players.len());
+    let message = match outcome {
+      _ if players == 1 => self.view.get_last()
+        .and_then(|last| last.players[0].death)
+        .map_or("", |cause| cause.message())
+        .to_string(),
+      Outcome::Winner(i) => match self.view.get_last() {
+        Some(last) => last.winner_message(i),
+        None => tr!("game.player_won", i + 1)
+      },
+      Outcome::Draw => tr!("game.draw").to_string()
+    };
+
+    self.view.game_over(&message)
+  }
+}
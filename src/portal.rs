@@ -0,0 +1,124 @@
+use rand::Rng;
+
+use crate::ui::{
+  dimensions::{Pos, Size},
+  Drawable,
+  theme,
+  ui_items::Symbol
+};
+
+#[derive(Copy, Clone)]
+pub struct Portal {
+  ends: [Pos; 2],
+  index: usize
+}
+
+impl Portal {
+  pub fn new(a: Pos, b: Pos, index: usize) -> Self {
+    Self { ends: [a, b], index }
+  }
+
+  pub fn get_ends(&self) -> [Pos; 2] {
+    self.ends
+  }
+
+  pub fn get_symbols(&self) -> [Symbol; 2] {
+    let theme = theme::current();
+    let color = theme.colors.portals[self.index % theme.colors.portals.len()];
+
+    self.ends.map(|pos| Symbol::new(pos).ch(theme.glyphs.portal).color(color))
+  }
+
+  pub fn contains(&self, pos: &Pos) -> bool {
+    self.ends.contains(pos)
+  }
+
+  pub fn partner(&self, pos: &Pos) -> Option<Pos> {
+    if self.ends[0] == *pos {
+      Some(self.ends[1])
+    }
+    else if self.ends[1] == *pos {
+      Some(self.ends[0])
+    }
+    else {
+      None
+    }
+  }
+}
+
+impl Drawable for Portal {
+  fn draw(&self) -> std::io::Result<()> {
+    let [a, b] = self.get_symbols();
+    a.draw()?;
+    b.draw()
+  }
+}
+
+pub fn generate_portals(
+  rng: &mut impl Rng, field_size: &Size, count: usize, snake_pos: &Pos
+) -> Vec<Portal> {
+  let mut portals: Vec<Portal> = Vec::new();
+  let mut taken: Vec<Pos> = Vec::new();
+
+  let mut random_pos = |taken: &Vec<Pos>| loop {
+    let pos = Pos::from((
+      rng.gen_range(3..=field_size.width),
+      rng.gen_range(2..=field_size.height)
+    ));
+
+    if !pos.is_overlaps(snake_pos) &&
+        taken.iter().all(|t| t.x.abs_diff(pos.x) + t.y.abs_diff(pos.y) > 2) {
+      break pos;
+    }
+  };
+
+  for i in 0..count {
+    let a = random_pos(&taken);
+    taken.push(a);
+    let b = random_pos(&taken);
+    taken.push(b);
+
+    portals.push(Portal::new(a, b, i));
+  }
+
+  portals
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::ui::dimensions::{Pos, Size};
+  use super::{Portal, generate_portals};
+
+  #[test]
+  fn test_portal_partner() {
+    let a = Pos::from((3, 3));
+    let b = Pos::from((10, 7));
+    let portal = Portal::new(a, b, 0);
+
+    assert!(portal.partner(&a) == Some(b));
+    assert!(portal.partner(&b) == Some(a));
+    assert!(portal.partner(&Pos::from((3, 4)))
+      .is_none());
+    assert!(portal.contains(&b));
+  }
+
+  #[test]
+  fn test_generate_portals() {
+    let snake_pos = Pos::from((5, 5));
+    let portals = generate_portals(
+      &mut rand::thread_rng(), &Size::from((30, 20)), 3, &snake_pos
+    );
+    assert_eq!(portals.len(), 3);
+
+    let ends: Vec<Pos> = portals.iter()
+      .flat_map(|p| p.get_ends())
+      .collect();
+
+    for (i, a) in ends.iter().enumerate() {
+      assert!(!a.is_overlaps(&snake_pos));
+      for b in &ends[i + 1..] {
+        assert!(a.x.abs_diff(b.x) + a.y.abs_diff(b.y) > 2);
+      }
+    }
+  }
+}
@@ -0,0 +1,72 @@
+use rand::{RngCore, SeedableRng, Error};
+
+#[derive(Clone, Debug)]
+pub struct GameRng {
+  state: u64
+}
+
+impl GameRng {
+  pub fn from_state(state: u64) -> Self {
+    Self { state }
+  }
+
+  pub fn get_state(&self) -> u64 {
+    self.state
+  }
+}
+
+impl RngCore for GameRng {
+  fn next_u32(&mut self) -> u32 {
+    (self.next_u64() >> 32) as u32
+  }
+
+  fn next_u64(&mut self) -> u64 {
+    self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+    let mut z = self.state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+  }
+
+  fn fill_bytes(&mut self, dest: &mut [u8]) {
+    for chunk in dest.chunks_mut(8) {
+      let bytes = self.next_u64().to_le_bytes();
+      chunk.copy_from_slice(&bytes[..chunk.len()]);
+    }
+  }
+
+  fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+    self.fill_bytes(dest);
+    Ok(())
+  }
+}
+
+impl SeedableRng for GameRng {
+  type Seed = [u8; 8];
+
+  fn from_seed(seed: Self::Seed) -> Self {
+    Self { state: u64::from_le_bytes(seed) }
+  }
+
+  fn seed_from_u64(state: u64) -> Self {
+    Self { state }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use rand::{Rng, SeedableRng};
+  use super::GameRng;
+
+  #[test]
+  fn test_rng_is_reproducible() {
+    let mut a = GameRng::seed_from_u64(7);
+    let mut b = GameRng::seed_from_u64(7);
+    let first: Vec<u32> = (0..5).map(|_| a.gen_range(0..100)).collect();
+    let second: Vec<u32> = (0..5).map(|_| b.gen_range(0..100)).collect();
+    assert_eq!(first, second);
+
+    let mut resumed = GameRng::from_state(a.get_state());
+    assert_eq!(resumed.gen::<u64>(), a.gen::<u64>());
+  }
+}
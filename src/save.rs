@@ -0,0 +1,128 @@
+use std::{fs, io, path::PathBuf};
+
+use crate::error::{self, SnakeError};
+use crate::level::Level;
+use crate::storage;
+use crate::tr;
+use crate::world::{World, GameOptions};
+
+pub struct SavedGame {
+  pub world: World,
+  pub options: GameOptions,
+  pub time: f64,
+  pub paused: bool
+}
+
+pub fn path() -> PathBuf {
+  storage::data_dir().join("save.txt")
+}
+
+pub fn exists() -> bool {
+  path().is_file()
+}
+
+pub fn remove() -> io::Result<()> {
+  fs::remove_file(path())
+}
+
+pub fn encode(world: &World, options: &GameOptions,
+    time: f64, paused: bool) -> String {
+
+  let mut text = format!(
+    "time {:.3}\npaused {}\narcade {}\n", time, paused as u8, options.arcade as u8
+  );
+  if let Some(level) = &options.level {
+    for line in level.encode().lines() {
+      text.push_str(&format!("level {}\n", line));
+    }
+  }
+
+  text + &world.encode()
+}
+
+pub fn store(world: &World, options: &GameOptions,
+    time: f64, paused: bool) -> error::Result<()> {
+
+  let path = path();
+  if let Some(dir) = path.parent() {
+    fs::create_dir_all(dir)?;
+  }
+
+  let tmp = path.with_extension("tmp");
+  fs::write(&tmp, encode(world, options, time, paused))?;
+  fs::rename(tmp, path)?;
+
+  Ok(())
+}
+
+impl SavedGame {
+  pub fn load() -> error::Result<SavedGame> {
+    match fs::read_to_string(path()) {
+      Ok(source) => Self::decode(&source),
+      Err(err) if err.kind() == io::ErrorKind::NotFound =>
+        Err(SnakeError::Save(0, tr!("save.none").to_string())),
+      Err(err) => Err(err.into())
+    }
+  }
+
+  pub fn decode(source: &str) -> error::Result<SavedGame> {
+    let mut time = 0.0;
+    let mut paused = false;
+    let mut options = GameOptions::default();
+    let mut level = Vec::new();
+    let mut rest = Vec::new();
+
+    for (i, line) in source.lines().enumerate() {
+      let words: Vec<&str> = line.split_whitespace().collect();
+
+      match words[..] {
+        ["time", value] => time = value.parse()
+          .map_err(|_| SnakeError::Save(i + 1, tr!("save.bad_time").to_string()))?,
+        ["paused", value] => paused = value == "1",
+        ["arcade", value] => options.arcade = value == "1",
+        ["level", ..] => level.push(line.trim_start()["level".len()..].trim()),
+        _ => {
+          rest.push(line);
+          continue;
+        }
+      }
+      rest.push("");
+    }
+
+    if !level.is_empty() {
+      options.level = Some(Level::parse(&level.join("\n"))?);
+    }
+    let world = World::decode(&rest.join("\n"))?;
+    Ok(SavedGame { world, options, time, paused })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::level::Level;
+  use crate::ui::dimensions::Size;
+  use crate::world::{World, GameOptions};
+  use super::{SavedGame, encode};
+
+  #[test]
+  fn test_saved_game() {
+    let options = GameOptions {
+      arcade: true,
+      level: Some(Level::parse("name Двор\nbrick 3 4\nrock 5 5 d").unwrap()),
+      ..GameOptions::default()
+    };
+    let world = World::new(Size::from((20, 10)), &options);
+    let source = encode(&world, &options, 12.5, true);
+
+    let saved = SavedGame::decode(&source).unwrap();
+    assert_eq!(saved.time, 12.5);
+    assert!(saved.paused);
+    assert!(saved.options.arcade);
+    let level = saved.options.level.unwrap();
+    assert_eq!((level.name.as_str(), level.bricks.len()), ("Двор", 1));
+    assert_eq!(saved.world.encode(), world.encode());
+
+    let broken = source.replace("time 12.5", "time soon");
+    assert!(SavedGame::decode(&broken).is_err());
+  }
+}
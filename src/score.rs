@@ -0,0 +1,172 @@
+use crate::tr;
+
+pub const COMBO_WINDOW_TICKS: u64 = 100;
+pub const MAX_COMBO: u64 = 4;
+
+#[derive(Clone, Default)]
+pub struct Score {
+  base: u64,
+  combo_bonus: u64,
+  boost_bonus: u64,
+  combo: u64,
+  best_combo: u64,
+  last_meal: Option<u64>
+}
+
+impl Score {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn eat(&mut self, value: u16, tick: u64, boost: bool) -> u64 {
+    let value = value as u64;
+
+    self.combo = match self.last_meal {
+      Some(last) if tick.saturating_sub(last) <= COMBO_WINDOW_TICKS =>
+        (self.combo + 1).min(MAX_COMBO),
+      _ => 0
+    };
+    self.best_combo = self.best_combo.max(self.combo);
+    self.last_meal = Some(tick);
+
+    let combo_bonus = value * self.combo;
+    let boost_bonus = if boost { value / 2 } else { 0 };
+
+    self.base += value;
+    self.combo_bonus += combo_bonus;
+    self.boost_bonus += boost_bonus;
+
+    value + combo_bonus + boost_bonus
+  }
+
+  pub fn expire(&mut self, tick: u64) -> bool {
+    match self.last_meal {
+      Some(last) if self.combo > 0 &&
+          tick.saturating_sub(last) > COMBO_WINDOW_TICKS => {
+        self.combo = 0;
+        true
+      },
+      _ => false
+    }
+  }
+
+  pub fn get_combo(&self) -> u64 {
+    self.combo
+  }
+
+  pub fn get_multiplier(&self) -> u64 {
+    self.combo + 1
+  }
+
+  pub fn total(&self) -> u64 {
+    self.base + self.combo_bonus + self.boost_bonus
+  }
+
+  pub fn breakdown(&self) -> Vec<(&'static str, u64)> {
+    vec![
+      (tr!("score.apples"), self.base),
+      (tr!("score.combo"), self.combo_bonus),
+      (tr!("score.boost"), self.boost_bonus),
+      (tr!("score.best_combo"), self.best_combo + 1),
+      (tr!("score.total"), self.total())
+    ]
+  }
+
+  pub fn encode(&self) -> String {
+    let last_meal = match self.last_meal {
+      Some(tick) => tick.to_string(),
+      None => "-".to_string()
+    };
+
+    format!("{},{},{},{},{},{}", self.base, self.combo_bonus,
+      self.boost_bonus, self.combo, self.best_combo, last_meal)
+  }
+
+  pub fn decode(word: &str) -> Option<Score> {
+    let fields: Vec<&str> = word.split(',').collect();
+    if fields.len() != 6 {
+      return None;
+    }
+
+    let number = |i: usize| fields[i].parse::<u64>().ok();
+    let last_meal = match fields[5] {
+      "-" => None,
+      tick => Some(tick.parse().ok()?)
+    };
+
+    Some(Score {
+      base: number(0)?,
+      combo_bonus: number(1)?,
+      boost_bonus: number(2)?,
+      combo: number(3)?,
+      best_combo: number(4)?,
+      last_meal
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{Score, COMBO_WINDOW_TICKS, MAX_COMBO};
+
+  #[test]
+  fn test_combo() {
+    let mut score = Score::new();
+
+    assert_eq!(score.eat(10, 0, false), 10);
+    assert_eq!(score.get_multiplier(), 1);
+    assert_eq!(score.eat(10, 50, false), 20);
+    assert_eq!(score.eat(20, 60, false), 60);
+    assert_eq!(score.get_combo(), 2);
+
+    assert_eq!(score.eat(10, 60 + COMBO_WINDOW_TICKS + 1, false), 10);
+    assert_eq!(score.get_combo(), 0);
+    assert_eq!(score.total(), 100);
+  }
+
+  #[test]
+  fn test_combo_cap_and_expire() {
+    let mut score = Score::new();
+
+    for tick in 0..10 {
+      score.eat(10, tick, false);
+    }
+    assert_eq!(score.get_combo(), MAX_COMBO);
+
+    assert!(!score.expire(9 + COMBO_WINDOW_TICKS));
+    assert!(score.expire(10 + COMBO_WINDOW_TICKS));
+    assert_eq!(score.get_combo(), 0);
+    assert!(!score.expire(11 + COMBO_WINDOW_TICKS));
+  }
+
+  #[test]
+  fn test_boost_bonus() {
+    let mut score = Score::new();
+
+    assert_eq!(score.eat(20, 0, true), 30);
+    let breakdown = score.breakdown();
+    assert_eq!(breakdown[0].1, 20);
+    assert_eq!(breakdown[2].1, 10);
+    assert_eq!(breakdown.last().unwrap().1, 30);
+  }
+
+  #[test]
+  fn test_encode_decode() {
+    let mut score = Score::new();
+    assert_eq!(score.encode(), "0,0,0,0,0,-");
+
+    score.eat(10, 5, true);
+    score.eat(20, 30, false);
+    let decoded = Score::decode(&score.encode()).unwrap();
+    assert_eq!(decoded.encode(), score.encode());
+    assert_eq!(decoded.total(), score.total());
+    assert_eq!(decoded.get_combo(), 1);
+
+    assert!(Score::decode("1,2,3").is_none());
+    assert!(Score::decode("1,2,3,4,5,x").is_none());
+
+    let mut future = Score::decode("10,0,0,1,1,500").unwrap();
+    assert!(!future.expire(20));
+    assert_eq!(future.eat(10, 20, false), 30);
+  }
+}
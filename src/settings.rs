@@ -0,0 +1,384 @@
+use std::{fs, io, path::PathBuf};
+
+use crossterm::style::Color;
+
+use crate::storage;
+use crate::tr;
+use crate::ui::theme::{Theme, Palette, GlyphSet, Skin};
+use crate::world::{InputMode, input};
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Speed {
+  Slow, Normal, Fast
+}
+
+impl Speed {
+  pub const ALL: [Speed; 3] = [Speed::Slow, Speed::Normal, Speed::Fast];
+
+  pub fn tick_ms(&self) -> u64 {
+    match self {
+      Speed::Slow   => 70,
+      Speed::Normal => 50,
+      Speed::Fast   => 35
+    }
+  }
+
+  pub fn name(&self) -> &'static str {
+    match self {
+      Speed::Slow   => tr!("speed.slow"),
+      Speed::Normal => tr!("speed.normal"),
+      Speed::Fast   => tr!("speed.fast")
+    }
+  }
+
+  pub fn key(&self) -> &'static str {
+    match self {
+      Speed::Slow   => "slow",
+      Speed::Normal => "normal",
+      Speed::Fast   => "fast"
+    }
+  }
+
+  pub fn next(&self) -> Self {
+    match self {
+      Speed::Slow   => Speed::Normal,
+      Speed::Normal => Speed::Fast,
+      Speed::Fast   => Speed::Slow
+    }
+  }
+
+  pub fn from_key(key: &str) -> Option<Self> {
+    Self::ALL.into_iter().find(|speed| speed.key() == key)
+  }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum KeyScheme {
+  Wasd, Esdf, Vim
+}
+
+impl KeyScheme {
+  pub const ALL: [KeyScheme; 3] = [KeyScheme::Wasd, KeyScheme::Esdf, KeyScheme::Vim];
+
+  pub fn name(&self) -> &'static str {
+    match self {
+      KeyScheme::Wasd => "WASD",
+      KeyScheme::Esdf => "ESDF",
+      KeyScheme::Vim  => "HJKL"
+    }
+  }
+
+  pub fn letters(&self) -> [char; 4] {
+    match self {
+      KeyScheme::Wasd => ['w', 's', 'a', 'd'],
+      KeyScheme::Esdf => ['e', 'd', 's', 'f'],
+      KeyScheme::Vim  => ['k', 'j', 'h', 'l']
+    }
+  }
+
+  pub fn key(&self) -> &'static str {
+    match self {
+      KeyScheme::Wasd => "wasd",
+      KeyScheme::Esdf => "esdf",
+      KeyScheme::Vim  => "vim"
+    }
+  }
+
+  pub fn next(&self) -> Self {
+    match self {
+      KeyScheme::Wasd => KeyScheme::Esdf,
+      KeyScheme::Esdf => KeyScheme::Vim,
+      KeyScheme::Vim  => KeyScheme::Wasd
+    }
+  }
+
+  pub fn from_key(key: &str) -> Option<Self> {
+    Self::ALL.into_iter().find(|scheme| scheme.key() == key)
+  }
+}
+
+const QWERTY: &str = "qwertyuiopasdfghjklzxcvbnm";
+const CYRILLIC: [&str; 2] = [
+  "йцукенгшщзфывапролдячсмить",
+  "йцукенгшщзфівапролдячсмить"
+];
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Layout {
+  Qwerty, Qwertz, Azerty, Dvorak, Colemak
+}
+
+impl Layout {
+  pub const ALL: [Layout; 5] = [
+    Layout::Qwerty, Layout::Qwertz, Layout::Azerty, Layout::Dvorak, Layout::Colemak
+  ];
+
+  pub fn name(&self) -> &'static str {
+    match self {
+      Layout::Qwerty  => "QWERTY",
+      Layout::Qwertz  => "QWERTZ",
+      Layout::Azerty  => "AZERTY",
+      Layout::Dvorak  => "Dvorak",
+      Layout::Colemak => "Colemak"
+    }
+  }
+
+  pub fn key(&self) -> &'static str {
+    match self {
+      Layout::Qwerty  => "qwerty",
+      Layout::Qwertz  => "qwertz",
+      Layout::Azerty  => "azerty",
+      Layout::Dvorak  => "dvorak",
+      Layout::Colemak => "colemak"
+    }
+  }
+
+  fn letters(&self) -> &'static str {
+    match self {
+      Layout::Qwerty  => QWERTY,
+      Layout::Qwertz  => "qwertzuiopasdfghjklyxcvbnm",
+      Layout::Azerty  => "azertyuiopqsdfghjklwxcvbn,",
+      Layout::Dvorak  => "',.pyfgcrlaoeuidhtn;qjkxbm",
+      Layout::Colemak => "qwfpgjluy;arstdhneizxcvbkm"
+    }
+  }
+
+  pub fn next(&self) -> Self {
+    let i = Self::ALL.iter().position(|layout| layout == self).unwrap();
+    Self::ALL[(i + 1) % Self::ALL.len()]
+  }
+
+  pub fn from_key(key: &str) -> Option<Self> {
+    Self::ALL.into_iter().find(|layout| layout.key() == key)
+  }
+
+  pub fn chars(&self, key: char) -> Vec<char> {
+    let Some(i) = QWERTY.chars().position(|ch| ch == key) else {
+      return vec![key];
+    };
+
+    [self.letters()].into_iter().chain(CYRILLIC)
+      .filter_map(|letters| letters.chars().nth(i))
+      .collect()
+  }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum BoostMode {
+  Toggle, Hold
+}
+
+impl BoostMode {
+  pub const ALL: [BoostMode; 2] = [BoostMode::Toggle, BoostMode::Hold];
+
+  pub fn name(&self) -> &'static str {
+    match self {
+      BoostMode::Toggle => tr!("boost.toggle"),
+      BoostMode::Hold   => tr!("boost.hold")
+    }
+  }
+
+  pub fn key(&self) -> &'static str {
+    match self {
+      BoostMode::Toggle => "toggle",
+      BoostMode::Hold   => "hold"
+    }
+  }
+
+  pub fn next(&self) -> Self {
+    match self {
+      BoostMode::Toggle => BoostMode::Hold,
+      BoostMode::Hold   => BoostMode::Toggle
+    }
+  }
+
+  pub fn from_key(key: &str) -> Option<Self> {
+    Self::ALL.into_iter().find(|mode| mode.key() == key)
+  }
+}
+
+const PALETTE_SIZE: usize = 4;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Settings {
+  pub speed: Speed,
+  pub palette: usize,
+  pub keys: KeyScheme,
+  pub theme: Palette,
+  pub glyphs: GlyphSet,
+  pub skin: Skin,
+  pub input_depth: usize,
+  pub input_mode: InputMode,
+  pub boost: BoostMode,
+  pub layout: Layout,
+  pub mouse_steer: bool
+}
+
+impl Default for Settings {
+  fn default() -> Self {
+    Self {
+      speed: Speed::Normal,
+      palette: 0,
+      keys: KeyScheme::Wasd,
+      theme: Palette::Classic,
+      glyphs: GlyphSet::Auto,
+      skin: Skin::Plain,
+      input_depth: input::DEFAULT_DEPTH,
+      input_mode: InputMode::Buffered,
+      boost: BoostMode::Hold,
+      layout: Layout::Qwerty,
+      mouse_steer: false
+    }
+  }
+}
+
+impl Settings {
+  pub fn player_colors(&self, player: usize) -> (Color, Color) {
+    self.theme().player_colors(player + self.palette)
+  }
+
+  pub fn color_name(&self) -> &'static str {
+    color_name(self.player_colors(0).0)
+  }
+
+  pub fn next_palette(&mut self) {
+    self.palette = (self.palette + 1) % PALETTE_SIZE;
+  }
+
+  pub fn next_input_depth(&mut self) {
+    self.input_depth = self.input_depth % input::MAX_DEPTH + 1;
+  }
+
+  pub fn mouse_steer_name(&self) -> &'static str {
+    if self.mouse_steer { tr!("switch.on") } else { tr!("switch.off") }
+  }
+
+  pub fn theme(&self) -> Theme {
+    Theme::new(self.theme, self.glyphs).skin(self.skin)
+  }
+
+  pub fn load() -> Self {
+    fs::read_to_string(path())
+      .map(|text| Self::decode(&text))
+      .unwrap_or_default()
+  }
+
+  pub fn store(&self) -> io::Result<()> {
+    fs::create_dir_all(storage::data_dir())?;
+    fs::write(path(), self.encode())
+  }
+
+  pub fn encode(&self) -> String {
+    format!(
+      "speed = {}\ncolor = {}\nkeys = {}\ntheme = {}\nglyphs = {}\nskin = {}\n\
+       input_depth = {}\ninput_mode = {}\nboost = {}\nlayout = {}\n\
+       mouse_steer = {}\n",
+      self.speed.key(), self.palette, self.keys.key(),
+      self.theme.key(), self.glyphs.key(), self.skin.key(),
+      self.input_depth, self.input_mode.key(), self.boost.key(),
+      self.layout.key(), if self.mouse_steer { "on" } else { "off" }
+    )
+  }
+
+  pub fn decode(text: &str) -> Self {
+    let pairs = storage::parse_pairs(text);
+    let value = |key: &str| pairs.get(key).map(String::as_str).unwrap_or("");
+    let default = Self::default();
+
+    Self {
+      speed: Speed::from_key(value("speed")).unwrap_or(default.speed),
+      palette: value("color").parse::<usize>()
+        .map_or(default.palette, |palette| palette % PALETTE_SIZE),
+      keys: KeyScheme::from_key(value("keys")).unwrap_or(default.keys),
+      theme: Palette::from_key(value("theme")).unwrap_or(default.theme),
+      glyphs: GlyphSet::from_key(value("glyphs")).unwrap_or(default.glyphs),
+      skin: Skin::from_key(value("skin")).unwrap_or(default.skin),
+      input_depth: value("input_depth").parse::<usize>().ok()
+        .filter(|depth| (1..=input::MAX_DEPTH).contains(depth))
+        .unwrap_or(default.input_depth),
+      input_mode: InputMode::from_key(value("input_mode"))
+        .unwrap_or(default.input_mode),
+      boost: BoostMode::from_key(value("boost")).unwrap_or(default.boost),
+      layout: Layout::from_key(value("layout")).unwrap_or(default.layout),
+      mouse_steer: value("mouse_steer") == "on"
+    }
+  }
+}
+
+pub fn path() -> PathBuf {
+  storage::data_dir().join("config.txt")
+}
+
+fn color_name(color: Color) -> &'static str {
+  match color {
+    Color::Green   => tr!("color.green"),
+    Color::Red     => tr!("color.red"),
+    Color::Blue    => tr!("color.blue"),
+    Color::Yellow  => tr!("color.yellow"),
+    Color::White   => tr!("color.white"),
+    Color::Cyan    => tr!("color.cyan"),
+    Color::Magenta => tr!("color.magenta"),
+    Color::Reset   => tr!("color.default"),
+    _              => tr!("color.other")
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::ui::theme::{Theme, Palette, GlyphSet, Skin};
+  use crate::world::InputMode;
+  use super::{color_name, Settings, Speed, KeyScheme, BoostMode, Layout, QWERTY, CYRILLIC};
+
+  #[test]
+  fn test_settings_cycle() {
+    let mut settings = Settings::default();
+    assert_eq!(settings.speed.next().next().next(), Speed::Normal);
+    assert_eq!(settings.keys.next(), KeyScheme::Esdf);
+
+    let theme = Theme::exact(settings.theme, settings.glyphs);
+    let colors = |player| theme.player_colors(player);
+    let first = colors(settings.palette);
+    settings.next_palette();
+    assert_eq!(colors(settings.palette), colors(settings.palette + 4));
+    assert!(colors(settings.palette + 3) == first);
+    assert_eq!(color_name(colors(settings.palette).0), "синий");
+
+    settings.input_depth = 4;
+    settings.next_input_depth();
+    assert_eq!(settings.input_depth, 1);
+
+    assert_eq!(Layout::Qwerty.chars('s'), ['s', 'ы', 'і']);
+    assert_eq!(Layout::Azerty.chars('w'), ['z', 'ц', 'ц']);
+    assert_eq!(Layout::Dvorak.chars('d'), ['e', 'в', 'в']);
+    assert_eq!(Layout::Qwerty.chars('b'), ['b', 'и', 'и']);
+    assert_eq!(Layout::Qwerty.chars('m'), ['m', 'ь', 'ь']);
+    for letters in Layout::ALL.map(|layout| layout.letters()).into_iter()
+        .chain(CYRILLIC) {
+      assert_eq!(letters.chars().count(), QWERTY.len());
+    }
+    assert_eq!(Layout::Colemak.next(), Layout::Qwerty);
+  }
+
+  #[test]
+  fn test_encode_decode() {
+    let settings = Settings {
+      speed: Speed::Fast,
+      palette: 2,
+      keys: KeyScheme::Vim,
+      theme: Palette::Retro,
+      glyphs: GlyphSet::Ascii,
+      skin: Skin::Gradient,
+      input_depth: 4,
+      input_mode: InputMode::Latest,
+      boost: BoostMode::Toggle,
+      layout: Layout::Dvorak,
+      mouse_steer: true
+    };
+
+    assert_eq!(Settings::decode(&settings.encode()), settings);
+    assert_eq!(Settings::decode("speed = warp\n# comment\nkeys = esdf"), Settings {
+      keys: KeyScheme::Esdf,
+      ..Settings::default()
+    });
+  }
+}
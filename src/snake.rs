@@ -1,14 +1,16 @@
-use std::{sync::{Arc, Mutex}, io::Result};
+use rand::Rng;
+use crossterm::style::Color;
 
-use crossterm::style::Color::{self, *};
+use crate::portal::Portal;
 
 use crate::ui::{
   dimensions::{Pos, Size},
   Drawable,
-  ui_items::Symbol, UI
+  theme::{self, GlyphSet, Palette, Pieces, Skin, Theme},
+  ui_items::Symbol
 };
 
-#[derive(Copy, Clone, PartialEq)]
+#[derive(Copy, Clone, PartialEq, Debug)]
 pub enum Direction { Up, Down, Left, Right }
 
 impl Direction {
@@ -38,16 +40,123 @@ impl Direction {
 
     false
   }
+
+  pub fn opposite(&self) -> Self {
+    match self {
+      Direction::Up    => Direction::Down,
+      Direction::Down  => Direction::Up,
+      Direction::Left  => Direction::Right,
+      Direction::Right => Direction::Left
+    }
+  }
+
+  pub fn between(from: Pos, to: Pos, max_size: Size) -> Option<Self> {
+    [Direction::Up, Direction::Down, Direction::Left, Direction::Right]
+      .into_iter()
+      .find(|dir| dir.next_pos(from, max_size) == to)
+  }
+
+  fn is_vertical(&self) -> bool {
+    matches!(self, Direction::Up | Direction::Down)
+  }
+
+  pub fn turn_toward(&self, from: Pos, to: Pos) -> Option<Self> {
+    let (near, far, turns) = if self.is_vertical() {
+      (from.x, to.x, [Direction::Left, Direction::Right])
+    }
+    else {
+      (from.y, to.y, [Direction::Up, Direction::Down])
+    };
+
+    match far.cmp(&near) {
+      std::cmp::Ordering::Less    => Some(turns[0]),
+      std::cmp::Ordering::Greater => Some(turns[1]),
+      std::cmp::Ordering::Equal   => None
+    }
+  }
+
+  fn head(&self, heads: [char; 4]) -> char {
+    match self {
+      Direction::Up    => heads[0],
+      Direction::Down  => heads[1],
+      Direction::Left  => heads[2],
+      Direction::Right => heads[3]
+    }
+  }
+
+  fn piece(&self, to: Direction, pieces: &Pieces) -> char {
+    if self.is_vertical() == to.is_vertical() {
+      return if to.is_vertical() { pieces.vertical } else { pieces.horizontal };
+    }
+
+    let (side, end) = if to.is_vertical() {
+      (self.opposite(), to)
+    }
+    else {
+      (to, self.opposite())
+    };
+
+    match (side, end) {
+      (Direction::Right, Direction::Down) => pieces.corners[0],
+      (Direction::Left, Direction::Down)  => pieces.corners[1],
+      (Direction::Right, Direction::Up)   => pieces.corners[2],
+      _                                   => pieces.corners[3]
+    }
+  }
+
+  pub fn next_pos(&self, mut pos: Pos, max_size: Size) -> Pos {
+    match self {
+      Direction::Up    => pos.y -= 1,
+      Direction::Down  => pos.y += 1,
+      Direction::Left  => pos.x -= 1,
+      Direction::Right => pos.x += 1
+    }
+
+    if pos.x == max_size.width + 2 {
+      pos.x = 2;
+    }
+    else if pos.x == 1 {
+      pos.x = max_size.width + 1;
+    }
+
+    if pos.y == 0 {
+      pos.y = max_size.height;
+    }
+    else if pos.y == max_size.height + 1 {
+      pos.y = 1;
+    }
+
+    pos
+  }
+}
+
+impl TryFrom<char> for Direction {
+  type Error = char;
+
+  fn try_from(ch: char) -> std::result::Result<Self, Self::Error> {
+    match ch.to_ascii_uppercase() {
+      'U' => Ok(Direction::Up),
+      'D' => Ok(Direction::Down),
+      'L' => Ok(Direction::Left),
+      'R' => Ok(Direction::Right),
+      _   => Err(ch)
+    }
+  }
 }
 
 #[derive(Copy, Clone)]
 pub struct SnakePart {
-  symbol: Symbol
+  symbol: Symbol,
+  dir: Direction
 }
 
 impl SnakePart {
   pub fn new(symbol: Symbol) -> SnakePart {
-    SnakePart { symbol }
+    SnakePart { symbol, dir: Direction::Right }
+  }
+
+  pub fn get_direction(&self) -> Direction {
+    self.dir
   }
 
   pub fn get_pos(&self) -> Pos {
@@ -58,30 +167,31 @@ impl SnakePart {
     self.symbol.pos = new_pos;
   }
 
+  pub fn get_symbol(&self) -> Symbol {
+    self.symbol
+  }
+
   pub fn set_color(&mut self, color: Color) {
     self.symbol.color = color;
   }
 
   pub fn update(&mut self, dir: Direction, max_size: Size) {
-    match dir {
-      Direction::Up    => self.symbol.pos.y -= 1,
-      Direction::Down  => self.symbol.pos.y += 1,
-      Direction::Left  => self.symbol.pos.x -= 1,
-      Direction::Right => self.symbol.pos.x += 1
-    }
+    self.symbol.pos = dir.next_pos(self.symbol.pos, max_size);
+    self.dir = dir;
+  }
 
-    if self.symbol.pos.x == max_size.width + 2 {
-      self.symbol.pos.x = 2;
-    }
-    else if self.symbol.pos.x == 1 {
-      self.symbol.pos.x = max_size.width + 1;
-    }
+  pub fn enter_portal(&mut self, dir: Direction,
+      max_size: Size, portals: &[Portal]) -> bool {
 
-    if self.symbol.pos.y == 0 {
-      self.symbol.pos.y = max_size.height;
+    let exit = portals.iter()
+      .find_map(|portal| portal.partner(&self.symbol.pos));
+
+    if let Some(exit) = exit {
+      self.symbol.pos = dir.next_pos(exit, max_size);
+      true
     }
-    else if self.symbol.pos.y == max_size.height + 1 {
-      self.symbol.pos.y = 1;
+    else {
+      false
     }
   }
 }
@@ -92,49 +202,155 @@ impl Drawable for SnakePart {
   }
 }
 
+#[derive(Clone)]
 pub struct Snake {
   parts: Vec<SnakePart>,
   dir: Direction,
-  field_size: Size
+  field_size: Size,
+  head_color: Color,
+  body_color: Color,
+  boost: bool,
+  theme: Theme
 }
 
 impl Snake {
-  pub fn new(field_size: Size, dir: Direction) -> Snake {
-    use rand::Rng;
-    let mut rng = rand::thread_rng();
+  pub fn new(rng: &mut impl Rng, field_size: Size, dir: Direction) -> Snake {
     let x = rng.gen_range(0..field_size.width);
     let y = rng.gen_range(0..field_size.height);
 
-    Snake {
-      parts: vec![
-        SnakePart::new(
-          Symbol::new(Pos::from((x + 2, y + 1)))
-            .ch('◇')
-            .color(Green)
-          )
-        ],
+    Self::spawn(field_size, dir, Pos::from((x + 2, y + 1)))
+  }
+
+  pub fn spawn(field_size: Size, dir: Direction, pos: Pos) -> Snake {
+    let theme = Theme::exact(Palette::Classic, GlyphSet::Unicode);
+    let (head_color, body_color) = theme.player_colors(0);
+
+    let mut snake = Snake {
+      parts: vec![SnakePart { symbol: Symbol::new(pos), dir }],
       dir,
-      field_size
+      field_size,
+      head_color,
+      body_color,
+      boost: false,
+      theme
+    };
+    snake.restyle();
+
+    snake
+  }
+
+  pub fn from_parts(field_size: Size, dir: Direction, parts: &[Pos]) -> Snake {
+    let mut snake = Self::spawn(field_size, dir, parts[0]);
+    for i in 1..parts.len() {
+      let fallback = if i == 1 { dir } else { snake.parts[i - 2].dir };
+      let entered = Direction::between(parts[i], parts[i - 1], field_size)
+        .unwrap_or(fallback);
+
+      snake.parts[i - 1].dir = entered;
+      snake.parts.push(SnakePart { symbol: Symbol::new(parts[i]), dir: entered });
     }
+    snake.restyle();
+
+    snake
+  }
+
+  pub fn colors(mut self, head_color: Color, body_color: Color) -> Self {
+    self.set_colors(head_color, body_color);
+    self
+  }
+
+  pub fn set_colors(&mut self, head_color: Color, body_color: Color) {
+    self.head_color = head_color;
+    self.body_color = body_color;
+    self.restyle();
+  }
+
+  pub fn theme(mut self, theme: Theme) -> Self {
+    self.set_theme(theme);
+    self
+  }
+
+  pub fn set_theme(&mut self, theme: Theme) {
+    self.theme = theme;
+    self.restyle();
+  }
+
+  pub fn restyle(&mut self) {
+    for i in 0..self.parts.len() {
+      self.restyle_part(i);
+    }
+  }
+
+  fn restyle_part(&mut self, i: usize) {
+    let theme = &self.theme;
+    let glyphs = &theme.glyphs;
+    let last = self.parts.len() - 1;
+    let directional = theme.skin != Skin::Plain;
+
+    let ch = match i {
+      0 if directional => self.dir.head(if self.boost {
+        glyphs.pieces.boost_heads
+      }
+      else {
+        glyphs.pieces.heads
+      }),
+      0 if self.boost => glyphs.boost_head,
+      0 => glyphs.head,
+      _ if !directional => glyphs.body,
+      _ if i == last => glyphs.pieces.tail,
+      _ => self.parts[i].dir.piece(self.parts[i - 1].dir, &glyphs.pieces)
+    };
+
+    let color = match i {
+      0 if self.boost => theme.colors.boost,
+      0 => self.head_color,
+      _ if theme.skin == Skin::Gradient => theme::blend(
+        self.head_color, self.body_color, i as f32 / last as f32
+      ),
+      _ => self.body_color
+    };
+
+    self.parts[i].symbol.ch = ch;
+    self.parts[i].symbol.color = color;
+  }
+
+  fn restyle_ends(&mut self) {
+    if self.theme.skin == Skin::Gradient {
+      return self.restyle();
+    }
+
+    let last = self.parts.len() - 1;
+    for i in [last.saturating_sub(1), last, 1.min(last), 0] {
+      self.restyle_part(i);
+    }
+  }
+
+  pub fn get_head_color(&self) -> Color {
+    self.head_color
+  }
+
+  pub fn get_direction(&self) -> Direction {
+    self.dir
   }
 
   pub fn get_parts(&self) -> &Vec<SnakePart> {
     &self.parts
   }
 
-  pub fn update(&mut self, ui: Arc<Mutex<UI>>) -> Result<()> {
-    let mut prev_pos = self.parts[0].get_pos();
-    let mut new_pos = prev_pos.clone();
-
-    self.parts[0].update(self.dir, self.field_size);
+  pub fn update(&mut self, portals: &[Portal]) -> Pos {
+    let vacated = self.parts.last().unwrap().get_pos();
 
-    for i in 1..self.parts.len() {
-      prev_pos = self.parts[i].get_pos();
-      self.parts[i].set_pos(new_pos);
-      new_pos = prev_pos;
+    for i in (1..self.parts.len()).rev() {
+      let prev = self.parts[i - 1];
+      self.parts[i].set_pos(prev.get_pos());
+      self.parts[i].dir = prev.dir;
     }
 
-    ui.lock().unwrap().draw(&Symbol::new(new_pos))
+    self.parts[0].update(self.dir, self.field_size);
+    self.parts[0].enter_portal(self.dir, self.field_size, portals);
+    self.restyle_ends();
+
+    vacated
   }
 
   pub fn set_direction(&mut self, dir: Direction) {
@@ -144,13 +360,9 @@ impl Snake {
   }
 
   pub fn add_part(&mut self) {
-    self.parts.push(
-      SnakePart::new(
-        Symbol::new(
-          self.parts.last().unwrap().get_pos()
-        ).ch('◆').color(DarkGreen)
-      )
-    );
+    let tail = *self.parts.last().unwrap();
+    self.parts.push(tail);
+    self.restyle_ends();
   }
 
   pub fn check_self_eaten(&self) -> bool {
@@ -181,13 +393,25 @@ impl Snake {
     let head = &mut self.parts[0];
     head.set_color(color);
   }
+
+  pub fn set_boost(&mut self, boost: bool) {
+    self.boost = boost;
+    self.restyle_part(0);
+  }
 }
 
 impl Drawable for Snake {
   fn draw(&self) -> std::io::Result<()> {
-    self.parts.first().unwrap().draw()?;
-    if let Some(second_part) = self.parts.get(1) {
-      second_part.draw()?;
+    if self.theme.skin == Skin::Gradient {
+      for part in self.parts.iter().rev() {
+        part.draw()?;
+      }
+      return Ok(());
+    }
+
+    let last = self.parts.len() - 1;
+    for i in [last, 1.min(last), 0] {
+      self.parts[i].draw()?;
     }
 
     Ok(())
@@ -200,30 +424,49 @@ mod tests {
     ui_items::Symbol,
     dimensions::{Pos, Size}
   };
-  use super::{Direction, SnakePart};
+  use crate::portal::Portal;
+  use crate::ui::theme;
+  use super::{Direction, Snake, SnakePart};
+
+  #[test]
+  fn test_turn_toward() {
+    let head = Pos::from((10, 5));
+    assert_eq!(Direction::Right.turn_toward(head, Pos::from((3, 2))),
+      Some(Direction::Up));
+    assert_eq!(Direction::Up.turn_toward(head, Pos::from((12, 1))),
+      Some(Direction::Right));
+    assert_eq!(Direction::Left.turn_toward(head, Pos::from((30, 5))), None);
+  }
 
   #[test]
   fn test_is_opposite() {
-    assert_eq!(
+    assert!(
       Direction::Up
-        .is_opposite(&Direction::Down), true);
-    assert_eq!(
+        .is_opposite(&Direction::Down));
+    assert!(
       Direction::Down
-        .is_opposite(&Direction::Up), true);
+        .is_opposite(&Direction::Up));
     
-    assert_eq!(
+    assert!(
       Direction::Left
-        .is_opposite(&Direction::Right), true);
-    assert_eq!(
+        .is_opposite(&Direction::Right));
+    assert!(
       Direction::Right
-        .is_opposite(&Direction::Left), true);
+        .is_opposite(&Direction::Left));
     
-    assert_eq!(
-      Direction::Left
-        .is_opposite(&Direction::Up), false);
-    assert_eq!(
-      Direction::Right
-        .is_opposite(&Direction::Down), false);
+    assert!(
+      !Direction::Left
+        .is_opposite(&Direction::Up));
+    assert!(
+      !Direction::Right
+        .is_opposite(&Direction::Down));
+  }
+
+  #[test]
+  fn test_direction_from_char() {
+    assert!(Direction::try_from('u') == Ok(Direction::Up));
+    assert!(Direction::try_from('R') == Ok(Direction::Right));
+    assert!(Direction::try_from('x').is_err());
   }
 
   #[test]
@@ -253,4 +496,47 @@ mod tests {
     snake_part.update(Direction::Up, Size::from((5, 4)));
     assert_eq!(snake_part.get_pos().y, 4);
   }
-}
\ No newline at end of file
+
+  #[test]
+  fn test_snake_part_portal() {
+    let max_size = Size::from((10, 10));
+    let portals = [Portal::new(Pos::from((5, 5)), Pos::from((8, 2)), 0)];
+    let mut snake_part = SnakePart::new(Symbol::new(Pos::from((4, 5))));
+
+    snake_part.update(Direction::Right, max_size);
+    assert!(snake_part.enter_portal(Direction::Right, max_size, &portals));
+    assert!(snake_part.get_pos() == Pos::from((9, 2)));
+
+    snake_part.update(Direction::Right, max_size);
+    assert!(!snake_part.enter_portal(Direction::Right, max_size, &portals));
+    assert!(snake_part.get_pos() == Pos::from((10, 2)));
+
+    snake_part.set_pos(Pos::from((8, 3)));
+    snake_part.update(Direction::Up, max_size);
+    assert!(snake_part.enter_portal(Direction::Up, max_size, &portals));
+    assert!(snake_part.get_pos() == Pos::from((5, 4)));
+  }
+
+  #[test]
+  fn test_parts_track_turns() {
+    let size = Size::from((10, 10));
+    let pieces = theme::current().glyphs.pieces;
+    assert_eq!(Direction::Right.piece(Direction::Up, &pieces), pieces.corners[3]);
+    assert_eq!(Direction::Down.piece(Direction::Right, &pieces), pieces.corners[2]);
+    assert_eq!(Direction::Left.piece(Direction::Left, &pieces), pieces.horizontal);
+    assert_eq!(Direction::between(Pos::from((11, 3)), Pos::from((2, 3)), size),
+      Some(Direction::Right));
+
+    let positions = [Pos::from((5, 5)), Pos::from((4, 5)), Pos::from((4, 6))];
+    let mut snake = Snake::from_parts(size, Direction::Right, &positions);
+    let dirs = |snake: &Snake| snake.get_parts().iter()
+      .map(|part| part.get_direction())
+      .collect::<Vec<_>>();
+    assert_eq!(dirs(&snake), [Direction::Right, Direction::Up, Direction::Up]);
+
+    snake.set_direction(Direction::Down);
+    assert_eq!(snake.update(&[]), Pos::from((4, 6)));
+    assert_eq!(dirs(&snake), [Direction::Down, Direction::Right, Direction::Up]);
+    assert_eq!(snake.get_parts()[2].get_pos(), Pos::from((4, 5)));
+  }
+}
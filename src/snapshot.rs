@@ -0,0 +1,384 @@
+use crate::ai::Difficulty;
+use crate::food::{FoodType, get_food_at};
+use crate::hazard::{Hazard, HazardKind};
+use crate::portal::Portal;
+use crate::snake::{Snake, Direction};
+use crate::tr;
+
+use crate::ui::{
+  dimensions::{Pos, Size},
+  theme,
+  ui_items::Symbol
+};
+
+use crate::world::{World, DeathCause};
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct PlayerSnapshot {
+  pub bot: Option<Difficulty>,
+  pub death: Option<DeathCause>,
+  pub boost: bool,
+  pub score: u64,
+  pub multiplier: u64,
+  pub dir: Direction,
+  pub parts: Vec<Pos>
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Snapshot {
+  pub tick: u64,
+  pub apple: (FoodType, Pos),
+  pub bricks: Vec<Pos>,
+  pub hazards: Vec<(HazardKind, Pos)>,
+  pub portals: Vec<[Pos; 2]>,
+  pub players: Vec<PlayerSnapshot>
+}
+
+impl From<&World> for Snapshot {
+  fn from(world: &World) -> Self {
+    let field = &world.field;
+
+    Snapshot {
+      tick: world.tick,
+      apple: (field.apple.get_type(), field.apple.get_pos()),
+      bricks: field.bricks.iter().map(|b| b.get_pos()).collect(),
+      hazards: field.hazards.iter()
+        .map(|h| (h.get_kind(), h.get_pos()))
+        .collect(),
+      portals: field.portals.iter().map(|p| p.get_ends()).collect(),
+      players: world.players.iter()
+        .map(|p| PlayerSnapshot {
+          bot: p.bot,
+          death: p.death,
+          boost: p.boost,
+          score: p.score.total(),
+          multiplier: p.score.get_multiplier(),
+          dir: p.snake.get_direction(),
+          parts: p.snake.get_parts().iter().map(|s| s.get_pos()).collect()
+        })
+        .collect()
+    }
+  }
+}
+
+impl Snapshot {
+  pub fn fits(&self, field_size: Size) -> bool {
+    std::iter::once(self.apple.1)
+      .chain(self.bricks.iter().copied())
+      .chain(self.hazards.iter().map(|(_, pos)| *pos))
+      .chain(self.portals.iter().flatten().copied())
+      .chain(self.players.iter().flat_map(|p| p.parts.iter().copied()))
+      .all(|pos| field_size.contains(&pos))
+  }
+
+  pub fn humans(&self) -> usize {
+    self.players.iter().filter(|p| p.bot.is_none()).count()
+  }
+
+  pub fn player_name(&self, player: usize) -> String {
+    match self.players[player].bot {
+      Some(difficulty) => tr!(
+        "name.bot", player + 1 - self.humans(), difficulty.name()
+      ),
+      None => tr!("name.player", player + 1)
+    }
+  }
+
+  pub fn winner_message(&self, player: usize) -> String {
+    match self.players.get(player).and_then(|p| p.bot) {
+      Some(difficulty) => tr!(
+        "game.bot_won", player + 1 - self.humans(), difficulty.name()
+      ),
+      None => tr!("game.player_won", player + 1)
+    }
+  }
+
+  pub fn snakes(&self, field_size: Size) -> Vec<Snake> {
+    let theme = theme::current();
+    self.players.iter().enumerate()
+      .filter(|(_, p)| p.death.is_none())
+      .map(|(i, p)| {
+        let (head, body) = theme.player_colors(i);
+        let mut snake = Snake::from_parts(field_size, p.dir, &p.parts)
+          .theme(theme)
+          .colors(head, body);
+        snake.set_boost(p.boost);
+        snake
+      })
+      .collect()
+  }
+
+  pub fn symbols(&self, field_size: Size) -> Vec<Symbol> {
+    let mut symbols: Vec<Symbol> = self.bricks.iter()
+      .map(|pos| get_food_at(FoodType::Brick, *pos).get_symbol())
+      .collect();
+
+    for (i, ends) in self.portals.iter().enumerate() {
+      let portal = Portal::new(ends[0], ends[1], i);
+      symbols.extend(portal.get_symbols());
+    }
+
+    symbols.push(get_food_at(self.apple.0, self.apple.1).get_symbol());
+    symbols.extend(self.hazards.iter()
+      .map(|(kind, pos)| Hazard::of_kind(*kind, *pos).get_symbol()));
+
+    for snake in self.snakes(field_size) {
+      symbols.extend(snake.get_parts().iter().rev().map(|p| p.get_symbol()));
+    }
+
+    symbols
+  }
+
+  pub fn encode(&self) -> String {
+    let mut out = vec![
+      self.tick.to_string(),
+      food_token(self.apple.0).to_string(),
+      pos_token(&self.apple.1)
+    ];
+
+    out.push(self.bricks.len().to_string());
+    out.extend(self.bricks.iter().map(pos_token));
+
+    out.push(self.hazards.len().to_string());
+    for (kind, pos) in &self.hazards {
+      out.push(hazard_token(*kind).to_string());
+      out.push(pos_token(pos));
+    }
+
+    out.push(self.portals.len().to_string());
+    for ends in &self.portals {
+      out.push(pos_token(&ends[0]));
+      out.push(pos_token(&ends[1]));
+    }
+
+    out.push(self.players.len().to_string());
+    for player in &self.players {
+      out.push(player.bot.map_or("-", |bot| bot.key()).to_string());
+      out.push(death_token(player.death));
+      out.push((player.boost as u8).to_string());
+      out.push(player.score.to_string());
+      out.push(player.multiplier.to_string());
+      out.push(dir_token(player.dir).to_string());
+      out.push(player.parts.len().to_string());
+      out.extend(player.parts.iter().map(pos_token));
+    }
+
+    out.join(" ")
+  }
+
+  pub fn decode(line: &str) -> Option<Snapshot> {
+    let mut words = line.split_whitespace();
+    let mut next = || words.next();
+
+    let tick = next()?.parse().ok()?;
+    let apple_type = parse_food(next()?)?;
+    let apple_pos = parse_pos(next()?)?;
+
+    let count: usize = next()?.parse().ok()?;
+    let mut bricks = Vec::new();
+    for _ in 0..count {
+      bricks.push(parse_pos(next()?)?);
+    }
+
+    let count: usize = next()?.parse().ok()?;
+    let mut hazards = Vec::new();
+    for _ in 0..count {
+      hazards.push((parse_hazard(next()?)?, parse_pos(next()?)?));
+    }
+
+    let count: usize = next()?.parse().ok()?;
+    let mut portals = Vec::new();
+    for _ in 0..count {
+      portals.push([parse_pos(next()?)?, parse_pos(next()?)?]);
+    }
+
+    let count: usize = next()?.parse().ok()?;
+    let mut players = Vec::new();
+    for _ in 0..count {
+      let bot = match next()? {
+        "-" => None,
+        name => Some(Difficulty::try_from(name).ok()?)
+      };
+      let death = parse_death(next()?)?;
+      let boost = next()? == "1";
+      let score = next()?.parse().ok()?;
+      let multiplier = next()?.parse().ok()?;
+      let dir = Direction::try_from(next()?.chars().next()?).ok()?;
+
+      let length: usize = next()?.parse().ok()?;
+      let mut parts = Vec::new();
+      for _ in 0..length {
+        parts.push(parse_pos(next()?)?);
+      }
+      if parts.is_empty() {
+        return None;
+      }
+
+      players.push(PlayerSnapshot {
+        bot, death, boost, score, multiplier, dir, parts
+      });
+    }
+
+    Some(Snapshot {
+      tick,
+      apple: (apple_type, apple_pos),
+      bricks, hazards, portals, players
+    })
+  }
+}
+
+pub fn pos_token(pos: &Pos) -> String {
+  format!("{},{}", pos.x, pos.y)
+}
+
+pub fn parse_pos(word: &str) -> Option<Pos> {
+  let (x, y) = word.split_once(',')?;
+  Some(Pos::from((x.parse().ok()?, y.parse().ok()?)))
+}
+
+pub fn dir_token(dir: Direction) -> char {
+  match dir {
+    Direction::Up    => 'U',
+    Direction::Down  => 'D',
+    Direction::Left  => 'L',
+    Direction::Right => 'R'
+  }
+}
+
+pub fn food_token(food_type: FoodType) -> char {
+  match food_type {
+    FoodType::GreenApple => 'g',
+    FoodType::GoldApple  => 'o',
+    FoodType::Brick      => 'b'
+  }
+}
+
+pub fn parse_food(word: &str) -> Option<FoodType> {
+  match word {
+    "g" => Some(FoodType::GreenApple),
+    "o" => Some(FoodType::GoldApple),
+    "b" => Some(FoodType::Brick),
+    _   => None
+  }
+}
+
+pub fn hazard_token(kind: HazardKind) -> char {
+  match kind {
+    HazardKind::Patrol   => 'p',
+    HazardKind::Creature => 'c',
+    HazardKind::Rock     => 'r'
+  }
+}
+
+pub fn parse_hazard(word: &str) -> Option<HazardKind> {
+  match word {
+    "p" => Some(HazardKind::Patrol),
+    "c" => Some(HazardKind::Creature),
+    "r" => Some(HazardKind::Rock),
+    _   => None
+  }
+}
+
+pub fn death_token(death: Option<DeathCause>) -> String {
+  match death {
+    None => "-".to_string(),
+    Some(DeathCause::SelfBite)   => "S".to_string(),
+    Some(DeathCause::Brick)      => "B".to_string(),
+    Some(DeathCause::HeadToHead) => "X".to_string(),
+    Some(DeathCause::Snake(j))   => format!("O{}", j),
+    Some(DeathCause::Hazard(kind)) => format!("H{}", hazard_token(kind))
+  }
+}
+
+pub fn parse_death(word: &str) -> Option<Option<DeathCause>> {
+  let cause = match word {
+    "-" => return Some(None),
+    "S" => DeathCause::SelfBite,
+    "B" => DeathCause::Brick,
+    "X" => DeathCause::HeadToHead,
+    _ if word.starts_with('O') => DeathCause::Snake(word[1..].parse().ok()?),
+    _ if word.starts_with('H') => DeathCause::Hazard(parse_hazard(&word[1..])?),
+    _ => return None
+  };
+
+  Some(Some(cause))
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::ai::Difficulty;
+  use crate::food::FoodType;
+  use crate::hazard::HazardKind;
+  use crate::snake::Direction;
+  use crate::tr;
+  use crate::ui::dimensions::{Pos, Size};
+  use crate::world::DeathCause;
+  use super::{Snapshot, PlayerSnapshot};
+
+  fn snapshot() -> Snapshot {
+    Snapshot {
+      tick: 42,
+      apple: (FoodType::GoldApple, Pos::from((5, 6))),
+      bricks: vec![Pos::from((3, 3)), Pos::from((7, 2))],
+      hazards: vec![(HazardKind::Rock, Pos::from((9, 9)))],
+      portals: vec![[Pos::from((4, 4)), Pos::from((20, 10))]],
+      players: vec![
+        PlayerSnapshot {
+          bot: None, death: None, boost: true, score: 120, multiplier: 2,
+          dir: Direction::Left,
+          parts: vec![Pos::from((10, 5)), Pos::from((11, 5))]
+        },
+        PlayerSnapshot {
+          bot: Some(Difficulty::Cautious),
+          death: Some(DeathCause::Snake(0)), boost: false,
+          score: 30, multiplier: 1, dir: Direction::Up,
+          parts: vec![Pos::from((12, 5))]
+        }
+      ]
+    }
+  }
+
+  #[test]
+  fn test_snapshot_roundtrip() {
+    let snapshot = snapshot();
+    let decoded = Snapshot::decode(&snapshot.encode()).unwrap();
+    assert_eq!(decoded, snapshot);
+  }
+
+  #[test]
+  fn test_snapshot_names() {
+    let snapshot = snapshot();
+    assert_eq!(snapshot.humans(), 1);
+    assert_eq!(snapshot.player_name(0), tr!("name.player", 1));
+    assert_eq!(snapshot.player_name(1),
+      tr!("name.bot", 1, Difficulty::Cautious.name()));
+    assert_eq!(snapshot.winner_message(1),
+      tr!("game.bot_won", 1, Difficulty::Cautious.name()));
+  }
+
+  #[test]
+  fn test_snapshot_decode_errors() {
+    assert!(Snapshot::decode("").is_none());
+    assert!(Snapshot::decode("1 g 5,6 1").is_none());
+    assert!(Snapshot::decode("1 q 5,6 0 0 0 0").is_none());
+    assert!(Snapshot::decode("1 g 5,6 18446744073709551615 1,1").is_none());
+    assert!(Snapshot::decode("1 g 5,6 0 0 0 1 - - 0 0 1 R 99999999999 1,1").is_none());
+  }
+
+  #[test]
+  fn test_snapshot_fits() {
+    let mut snapshot = snapshot();
+    assert!(snapshot.fits(Size::from((30, 20))));
+    assert!(!snapshot.fits(Size::from((10, 20))));
+
+    snapshot.players[0].parts.push(Pos::from((12, 0)));
+    assert!(!snapshot.fits(Size::from((30, 20))));
+  }
+
+  #[test]
+  fn test_snapshot_symbols() {
+    let symbols = snapshot().symbols(Size::from((30, 20)));
+    assert_eq!(symbols.len(), 2 + 2 + 1 + 1 + 2);
+    assert!(symbols.last().unwrap().pos == Pos::from((10, 5)));
+  }
+}
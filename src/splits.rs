@@ -0,0 +1,140 @@
+use std::{fs, io, path::PathBuf};
+
+use crate::storage;
+use crate::world::GameOptions;
+use crate::ui::dimensions::Size;
+
+pub const SPLIT_APPLES: usize = 5;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Split {
+  pub index: usize,
+  pub segment: f64,
+  pub delta: Option<f64>
+}
+
+pub struct Splits {
+  key: String,
+  last: f64,
+  segments: Vec<f64>,
+  best: Vec<f64>
+}
+
+impl Splits {
+  pub fn new(field_size: Size, options: &GameOptions) -> Self {
+    let key = key(field_size, options);
+    let best = fs::read_to_string(path())
+      .map(|text| best_for(&text, &key))
+      .unwrap_or_default();
+
+    Self { key, last: 0.0, segments: Vec::new(), best }
+  }
+
+  pub fn is_due(apples: usize) -> bool {
+    apples > 0 && apples.is_multiple_of(SPLIT_APPLES)
+  }
+
+  pub fn split(&mut self, time: f64) -> Split {
+    let segment = time - self.last;
+    let index = self.segments.len() + 1;
+    let delta = self.best.get(index - 1).map(|best| segment - best);
+
+    self.last = time;
+    self.segments.push(segment);
+    Split { index, segment, delta }
+  }
+
+  pub fn reset(&mut self) {
+    self.best = self.merged_best();
+    self.last = 0.0;
+    self.segments.clear();
+  }
+
+  pub fn merged_best(&self) -> Vec<f64> {
+    let count = self.best.len().max(self.segments.len());
+    (0..count)
+      .map(|i| match (self.best.get(i), self.segments.get(i)) {
+        (Some(best), Some(segment)) => best.min(*segment),
+        (best, segment) => *best.or(segment).unwrap()
+      })
+      .collect()
+  }
+
+  pub fn store(&mut self) -> io::Result<()> {
+    if self.segments.is_empty() {
+      return Ok(());
+    }
+
+    self.best = self.merged_best();
+    let times: Vec<String> = self.best.iter()
+      .map(|time| format!("{:.3}", time))
+      .collect();
+    let line = format!("{} = {}", self.key, times.join(" "));
+
+    let mut lines: Vec<String> = fs::read_to_string(path())
+      .unwrap_or_default()
+      .lines()
+      .filter(|line| line.split_once('=')
+        .is_none_or(|(key, _)| key.trim() != self.key))
+      .map(String::from)
+      .collect();
+    lines.push(line);
+
+    fs::create_dir_all(storage::data_dir())?;
+    fs::write(path(), lines.join("\n") + "\n")
+  }
+}
+
+fn key(field_size: Size, options: &GameOptions) -> String {
+  let mut key = field_size.to_string();
+  if options.arcade {
+    key.push_str(" arcade");
+  }
+  if let Some(level) = &options.level {
+    let name: String = level.name.split_whitespace().collect::<Vec<_>>().join("_");
+    key.push_str(&format!(" level:{}", name.replace(['=', '#'], "_")));
+  }
+
+  key
+}
+
+fn best_for(text: &str, key: &str) -> Vec<f64> {
+  storage::parse_pairs(text).get(key)
+    .map(|times| times.split_whitespace()
+      .map_while(|time| time.parse().ok())
+      .collect())
+    .unwrap_or_default()
+}
+
+pub fn path() -> PathBuf {
+  storage::data_dir().join("splits.txt")
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::ui::dimensions::Size;
+  use crate::world::GameOptions;
+  use super::{Splits, best_for, key};
+
+  #[test]
+  fn test_splits_compare_with_best() {
+    let options = GameOptions { arcade: true, ..GameOptions::default() };
+    let key = key(Size::from((40, 16)), &options);
+    assert_eq!(key, "40x16 arcade");
+
+    let best = best_for("# best\n40x16 = 1.0\n40x16 arcade = 10.000 12.5\n", &key);
+    assert_eq!(best, [10.0, 12.5]);
+
+    let mut splits = Splits { key, last: 0.0, segments: Vec::new(), best };
+    assert!(!Splits::is_due(4));
+    assert!(Splits::is_due(10));
+
+    let first = splits.split(9.5);
+    assert_eq!((first.index, first.segment, first.delta), (1, 9.5, Some(-0.5)));
+    let second = splits.split(23.0);
+    assert_eq!(second.delta, Some(1.0));
+    let third = splits.split(30.0);
+    assert_eq!(third.delta, None);
+    assert_eq!(splits.merged_best(), [9.5, 12.5, 7.0]);
+  }
+}
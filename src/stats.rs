@@ -0,0 +1,310 @@
+use std::{fs, io, path::PathBuf, sync::Mutex};
+
+use crate::food::FoodType;
+use crate::json::{Object, ToJson};
+use crate::storage;
+use crate::tr;
+use crate::ui::format_time;
+use crate::world::{World, Event};
+
+pub const CAUSES: [&str; 7] = [
+  "self", "brick", "snake", "head_to_head", "patrol", "creature", "rock"
+];
+
+static SESSION: Mutex<Stats> = Mutex::new(Stats::new());
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Format {
+  Table, Csv, Json
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Stats {
+  pub games: u64,
+  pub green_apples: u64,
+  pub gold_apples: u64,
+  pub longest: usize,
+  pub play_time: f64,
+  pub ticks: u64,
+  pub boost_ticks: u64,
+  pub deaths: [u64; CAUSES.len()]
+}
+
+impl Default for Stats {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl Stats {
+  pub const fn new() -> Self {
+    Self {
+      games: 0,
+      green_apples: 0,
+      gold_apples: 0,
+      longest: 0,
+      play_time: 0.0,
+      ticks: 0,
+      boost_ticks: 0,
+      deaths: [0; CAUSES.len()]
+    }
+  }
+
+  pub fn record(&mut self, world: &World, events: &[Event]) {
+    let human = |i: usize| world.players[i].bot.is_none();
+
+    for player in world.players.iter().filter(|p| p.bot.is_none()) {
+      self.longest = self.longest.max(player.snake.get_parts().len() - 1);
+      if player.is_alive() {
+        self.ticks += 1;
+        self.boost_ticks += player.boost as u64;
+      }
+    }
+
+    for event in events {
+      match event {
+        Event::AppleEaten { player, food, .. } if human(*player) => match food {
+          FoodType::GoldApple => self.gold_apples += 1,
+          _ => self.green_apples += 1
+        },
+        Event::PlayerDied { player, cause } if human(*player) => {
+          if let Some(i) = CAUSES.iter().position(|key| *key == cause.key()) {
+            self.deaths[i] += 1;
+          }
+        },
+        _ => ()
+      }
+    }
+  }
+
+  pub fn merge(&mut self, other: &Stats) {
+    self.games += other.games;
+    self.green_apples += other.green_apples;
+    self.gold_apples += other.gold_apples;
+    self.longest = self.longest.max(other.longest);
+    self.play_time += other.play_time;
+    self.ticks += other.ticks;
+    self.boost_ticks += other.boost_ticks;
+    for (total, count) in self.deaths.iter_mut().zip(other.deaths) {
+      *total += count;
+    }
+  }
+
+  pub fn boost_percent(&self) -> f64 {
+    self.boost_ticks as f64 * 100.0 / self.ticks.max(1) as f64
+  }
+
+  pub fn load() -> Self {
+    fs::read_to_string(path())
+      .map(|text| Self::decode(&text))
+      .unwrap_or_default()
+  }
+
+  pub fn store(&self) -> io::Result<()> {
+    fs::create_dir_all(storage::data_dir())?;
+    fs::write(path(), self.encode())
+  }
+
+  pub fn encode(&self) -> String {
+    let mut text = format!(
+      "games = {}\ngreen_apples = {}\ngold_apples = {}\nlongest = {}\n\
+       play_time = {:.1}\nticks = {}\nboost_ticks = {}\n",
+      self.games, self.green_apples, self.gold_apples, self.longest,
+      self.play_time, self.ticks, self.boost_ticks
+    );
+
+    for (cause, count) in CAUSES.iter().zip(self.deaths) {
+      text.push_str(&format!("deaths.{} = {}\n", cause, count));
+    }
+
+    text
+  }
+
+  pub fn decode(text: &str) -> Self {
+    let pairs = storage::parse_pairs(text);
+    let number = |key: &str| pairs.get(key)
+      .and_then(|value| value.parse::<u64>().ok())
+      .unwrap_or(0);
+
+    let mut stats = Self {
+      games: number("games"),
+      green_apples: number("green_apples"),
+      gold_apples: number("gold_apples"),
+      longest: number("longest") as usize,
+      play_time: pairs.get("play_time")
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(0.0),
+      ticks: number("ticks"),
+      boost_ticks: number("boost_ticks"),
+      ..Self::new()
+    };
+
+    for (i, cause) in CAUSES.iter().enumerate() {
+      stats.deaths[i] = number(&format!("deaths.{}", cause));
+    }
+
+    stats
+  }
+
+  pub fn rows(&self) -> Vec<(String, String)> {
+    let mut rows = vec![
+      (tr!("stats.games").to_string(), self.games.to_string()),
+      (tr!("stats.green_apples").to_string(), self.green_apples.to_string()),
+      (tr!("stats.gold_apples").to_string(), self.gold_apples.to_string()),
+      (tr!("stats.longest").to_string(), self.longest.to_string()),
+      (tr!("stats.play_time").to_string(), format_time(self.play_time)),
+      (tr!("stats.boost").to_string(), format!("{:.1}%", self.boost_percent()))
+    ];
+
+    for (cause, count) in CAUSES.iter().zip(self.deaths) {
+      let key = format!("stats.death_{}", cause);
+      rows.push((tr!(&key).to_string(), count.to_string()));
+    }
+
+    rows
+  }
+
+  pub fn to_csv(&self) -> String {
+    let mut header = vec![
+      "games", "green_apples", "gold_apples", "longest", "play_time",
+      "boost_percent"
+    ].into_iter().map(String::from).collect::<Vec<_>>();
+    let mut values = vec![
+      self.games.to_string(),
+      self.green_apples.to_string(),
+      self.gold_apples.to_string(),
+      self.longest.to_string(),
+      format!("{:.1}", self.play_time),
+      format!("{:.1}", self.boost_percent())
+    ];
+
+    for (cause, count) in CAUSES.iter().zip(self.deaths) {
+      header.push(format!("deaths_{}", cause));
+      values.push(count.to_string());
+    }
+
+    format!("{}\n{}", header.join(","), values.join(","))
+  }
+
+  pub fn export(&self, format: Format) -> String {
+    match format {
+      Format::Table => table(&[(tr!("stats.lifetime"), self)]).join("\n"),
+      Format::Csv   => self.to_csv(),
+      Format::Json  => self.to_json()
+    }
+  }
+}
+
+impl ToJson for Stats {
+  fn to_json(&self) -> String {
+    let mut deaths = Object::new();
+    for (cause, count) in CAUSES.iter().zip(self.deaths) {
+      deaths = deaths.field(cause, &count);
+    }
+
+    Object::new()
+      .field("games", &self.games)
+      .field("green_apples", &self.green_apples)
+      .field("gold_apples", &self.gold_apples)
+      .field("longest", &self.longest)
+      .field("play_time", &self.play_time)
+      .field("boost_percent", &self.boost_percent())
+      .field("deaths", &deaths)
+      .to_json()
+  }
+}
+
+pub fn table(columns: &[(&str, &Stats)]) -> Vec<String> {
+  let columns: Vec<(&str, Vec<(String, String)>)> = columns.iter()
+    .map(|(title, stats)| (*title, stats.rows()))
+    .collect();
+  let labels: Vec<String> = columns.first()
+    .map(|(_, rows)| rows.iter().map(|(label, _)| label.clone()).collect())
+    .unwrap_or_default();
+
+  let label_width = labels.iter().map(|label| label.chars().count()).max()
+    .unwrap_or(0);
+  let widths: Vec<usize> = columns.iter()
+    .map(|(title, rows)| rows.iter()
+      .map(|(_, value)| value.chars().count())
+      .chain([title.chars().count()])
+      .max()
+      .unwrap_or(0))
+    .collect();
+
+  let mut lines = vec![columns.iter().zip(&widths).fold(
+    " ".repeat(label_width),
+    |line, ((title, _), width)| format!("{}  {: >2$}", line, title, width)
+  )];
+
+  for (i, label) in labels.iter().enumerate() {
+    lines.push(columns.iter().zip(&widths).fold(
+      format!("{: <1$}", label, label_width),
+      |line, ((_, rows), width)| format!("{}  {: >2$}", line, rows[i].1, width)
+    ));
+  }
+
+  lines
+}
+
+pub fn session() -> Stats {
+  *SESSION.lock().unwrap()
+}
+
+pub fn commit(game: &Stats) -> io::Result<()> {
+  SESSION.lock().unwrap().merge(game);
+
+  let mut lifetime = Stats::load();
+  lifetime.merge(game);
+  lifetime.store()
+}
+
+pub fn path() -> PathBuf {
+  storage::data_dir().join("stats.txt")
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::food::FoodType;
+  use crate::json::ToJson;
+  use crate::snake::Direction;
+  use crate::testing::{empty_world, place, put_apple, put_brick};
+  use crate::ui::dimensions::Pos;
+  use super::{Stats, table};
+
+  #[test]
+  fn test_record_and_encode() {
+    let mut world = empty_world((20, 10), 1, Vec::new());
+    place(&mut world, 0, Direction::Right, Pos::from((5, 5)));
+    put_apple(&mut world, FoodType::GoldApple, Pos::from((6, 5)));
+    put_brick(&mut world, Pos::from((9, 5)));
+    world.players[0].toggle_boost();
+
+    let mut stats = Stats::new();
+    while !world.is_over() {
+      let events = world.tick();
+      stats.record(&world, &events);
+    }
+
+    assert_eq!(stats.gold_apples, 1);
+    assert_eq!(stats.longest, 1);
+    assert_eq!(stats.deaths[1], 1);
+    assert_eq!(stats.boost_percent(), 100.0);
+
+    stats.games = 2;
+    stats.play_time = 12.5;
+    let mut total = Stats::decode(&stats.encode());
+    assert_eq!(total, stats);
+    total.merge(&stats);
+    assert_eq!(total.games, 4);
+    assert_eq!(total.deaths[1], 2);
+
+    assert!(stats.to_csv().starts_with("games,green_apples,"));
+    assert!(stats.to_csv().ends_with("\n2,0,1,1,12.5,100.0,0,1,0,0,0,0,0"));
+    assert!(stats.to_json().contains(r#""deaths":{"self":0,"brick":1,"#));
+    let lines = table(&[("a", &stats), ("б", &total)]);
+    assert_eq!(lines[0].split_whitespace().collect::<Vec<_>>(), ["a", "б"]);
+    assert_eq!(lines[1].split_whitespace().collect::<Vec<_>>(),
+      ["Игр", "сыграно", "2", "4"]);
+  }
+}
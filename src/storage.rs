@@ -0,0 +1,31 @@
+use std::{collections::HashMap, env, path::PathBuf};
+
+pub fn data_dir() -> PathBuf {
+  if let Some(dir) = env::var_os("SNAKE_HOME") {
+    return PathBuf::from(dir);
+  }
+
+  if cfg!(windows) {
+    if let Some(dir) = env::var_os("APPDATA") {
+      return PathBuf::from(dir).join("snake");
+    }
+  }
+
+  if let Some(dir) = env::var_os("XDG_DATA_HOME").filter(|dir| !dir.is_empty()) {
+    return PathBuf::from(dir).join("snake");
+  }
+
+  match env::var_os("HOME") {
+    Some(home) => PathBuf::from(home).join(".local/share/snake"),
+    None => PathBuf::from(".snake")
+  }
+}
+
+pub fn parse_pairs(text: &str) -> HashMap<String, String> {
+  text.lines()
+    .map(str::trim)
+    .filter(|line| !line.is_empty() && !line.starts_with('#'))
+    .filter_map(|line| line.split_once('='))
+    .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+    .collect()
+}
@@ -0,0 +1,34 @@
+use crate::ai::Difficulty;
+use crate::food::{FoodType, get_food_at};
+use crate::level::Level;
+use crate::snake::{Snake, Direction};
+use crate::ui::dimensions::{Pos, Size};
+use crate::world::{World, GameOptions};
+
+pub const SEED: u64 = 17;
+
+pub fn empty_world(size: (u16, u16), players: usize,
+    bots: Vec<Difficulty>) -> World {
+
+  let options = GameOptions {
+    level: Some(Level::parse("").unwrap()),
+    players,
+    bots,
+    seed: Some(SEED),
+    ..GameOptions::default()
+  };
+
+  World::new(Size::from(size), &options)
+}
+
+pub fn place(world: &mut World, player: usize, dir: Direction, pos: Pos) {
+  world.players[player].snake = Snake::spawn(world.field_size, dir, pos);
+}
+
+pub fn put_apple(world: &mut World, kind: FoodType, pos: Pos) {
+  world.field.apple = get_food_at(kind, pos);
+}
+
+pub fn put_brick(world: &mut World, pos: Pos) {
+  world.field.bricks.push(get_food_at(FoodType::Brick, pos));
+}
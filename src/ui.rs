@@ -1,28 +1,47 @@
 pub mod ui_items;
+pub mod cast;
 pub mod dimensions;
+pub mod menu;
+pub mod renderer;
+pub mod theme;
 mod staticui;
 
 use dimensions::{Pos, Size};
 
+use cast::out;
 use staticui::StaticUI;
 
 use ui_items::{
-  Symbol, Label, PopupMessage
+  Symbol, Label, Button, PopupMessage, PopupWindow
 };
 
 use crate::error::{*, self};
+use crate::settings::{KeyScheme, Layout};
+use crate::splits::Split;
+use crate::tr;
 
-use std::io::{Result, stdout};
+use std::{
+  cell::Cell,
+  io::Result
+};
 
 use crossterm::{
   terminal::{*, self},
   cursor,
-  style::{Color::*, Stylize},
+  event::{EnableMouseCapture, DisableMouseCapture},
+  style::{Color, Print, Stylize, StyledContent},
   execute
 };
 
 const MINIMUM_WIDTH: u16 = 80;
 const MINIMUM_HEIGHT: u16 = 14;
+const POPUP_WIDTH: u16 = 27;
+const SPLIT_WIDTH: usize = 20;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Click {
+  Pause, Restart, Menu
+}
 
 pub trait Drawable {
   fn draw(&self) -> Result<()>;
@@ -30,97 +49,291 @@ pub trait Drawable {
 
 pub struct UI {
   pub field_size: Size,
-  score: Label,
-  s_length: Label,
-  time: Label
+  static_ui: StaticUI,
+  colors: Vec<Color>,
+  score: Vec<Label>,
+  combo: Label,
+  s_length: Vec<Label>,
+  time: Label,
+  split: Label,
+  buttons: Vec<(Button, Click)>,
+  popup_x: Cell<u16>
 }
 
 impl UI {
-  pub fn new() -> error::Result<UI> {
-    let (mut width, mut height) = crossterm::terminal::size()?;
+  pub fn new(players: usize) -> error::Result<UI> {
+    let (width, height) = crossterm::terminal::size()?;
 
     if width < MINIMUM_WIDTH || height < MINIMUM_HEIGHT {
       return Err(SnakeError::Dimension(MINIMUM_WIDTH, MINIMUM_HEIGHT));
     }
 
-    enable_raw_mode()?;
-    
+    Self::with_field_size(players, Size {
+      width:  27 + (width  - MINIMUM_WIDTH),
+      height: 12 + (height - MINIMUM_HEIGHT)
+    })
+  }
+
+  pub fn with_field_size(players: usize,
+      field_size: Size) -> error::Result<UI> {
+
+    let width = field_size.width;
+    let minimum = Self::minimum_size(field_size);
+    let terminal_size = cast::size()?;
+
+    if terminal_size.0 < minimum.width || terminal_size.1 < minimum.height {
+      return Err(SnakeError::Dimension(minimum.width, minimum.height));
+    }
+
+    if !cast::is_headless() {
+      enable_raw_mode()?;
+    }
+
     execute!(
-      stdout(),
+      out(),
       terminal::Clear(ClearType::All),
-      cursor::Hide
+      cursor::Hide,
+      EnableMouseCapture
     )?;
 
-    width  = 27 + (width  - MINIMUM_WIDTH);
-    height = 12 + (height - MINIMUM_HEIGHT);
-    let field_size = Size{ width, height };
-    let static_ui = StaticUI::new(field_size);
+    let static_ui = StaticUI::new(field_size, players);
     static_ui.draw()?;
     
-    Ok(UI {
+    let mut ui = UI {
       field_size,
-      score: Label::new(
-        Pos::from((width + 11, 1)),
-        "0".to_string()
-          .with(Magenta)
-          .bold()
-        ),
-      s_length: Label::new(
-        Pos::from((width + 17, 2)),
-        "0".to_string()
-          .with(Magenta)
+      static_ui,
+      colors: Vec::new(),
+      score: Vec::new(),
+      combo: Label::new(
+        Pos::from(((value_x(width, "ui.score") + 10).min(width + 21), 1)),
+        "".to_string()
+          .with(theme::current().colors.combo)
           .bold()
         ),
+      s_length: Vec::new(),
       time: Label::new(
-        Pos::from((width + 12, 3)),
-        "0м0.0с".to_string()
-          .with(Magenta)
+        Pos::from((value_x(width, "ui.time"), 3)),
+        format_time(0.0)
+          .with(theme::current().colors.accent)
           .bold()
-        )
-    })
+        ),
+      split: Label::new(
+        Pos::from((3, field_size.height + 1)),
+        split_message(None)
+      ),
+      buttons: Vec::new(),
+      popup_x: Cell::new(u16::MAX)
+    };
+    ui.set_players(&[]);
+
+    Ok(ui)
+  }
+
+  pub fn minimum_size(field_size: Size) -> Size {
+    Size {
+      width:  MINIMUM_WIDTH.max(field_size.width + 53),
+      height: MINIMUM_HEIGHT.max(field_size.height + 2)
+    }
+  }
+
+  pub fn set_players(&mut self, colors: &[Color]) {
+    let width = self.field_size.width;
+    let score_x = value_x(width, "ui.score");
+    let length_x = value_x(width, "ui.length");
+    let (score_x, length_x) = if colors.len() > 1 {
+      ([score_x, score_x + 7], [length_x, length_x + 4])
+    }
+    else {
+      ([score_x, 0], [length_x, 0])
+    };
+
+    self.colors = if colors.len() > 1 {
+      colors.to_vec()
+    }
+    else {
+      vec![theme::current().colors.accent]
+    };
+
+    let label = |x, y, color: &Color| Label::new(
+      Pos::from((x, y)),
+      "0".to_string()
+        .with(*color)
+        .bold()
+    );
+
+    self.score = self.colors.iter()
+      .zip(score_x)
+      .map(|(color, x)| label(x, 1, color))
+      .collect();
+    self.s_length = self.colors.iter()
+      .zip(length_x)
+      .map(|(color, x)| label(x, 2, color))
+      .collect();
   }
 
   pub fn clear_popup_message(&self) -> Result<()> {
-    let terminal_size = terminal::size()?;
-    let start_x = terminal_size.0 - 27;
+    let terminal_size = cast::size()?;
+    let default_x = terminal_size.0 - POPUP_WIDTH;
+    let start_x = self.popup_x.replace(u16::MAX).min(default_x);
     for x in start_x..terminal_size.0 {
       for y in 1..5 {
         Symbol::new(Pos::from((x, y))).draw()?;
       }
     }
 
+    if start_x < default_x {
+      self.redraw_stats()?;
+    }
+
     Ok(())
   }
 
   pub fn print_popup_message(&self, message: &str) -> Result<()> {
-    let mut x = terminal::size()?.0 - 14;
-    x -= (message.chars().count() as u16 / 2) + 2;
-    
+    let width = cast::size()?.0;
+    let count = message.chars().count() as u16;
+    let x = (width - 14)
+      .saturating_sub(count / 2 + 2)
+      .min(width.saturating_sub(count + 4));
+    self.popup_x.set(self.popup_x.get().min(x));
+
     PopupMessage::new(Pos::from((x, 1)), message.to_string()).draw()
   }
 
-  pub fn print_stats(&mut self, score: &u16, s_length: &u16) -> Result<()> {
-    self.score.set_message(
-      score.to_string().with(Magenta).bold()
+  fn redraw_stats(&self) -> Result<()> {
+    self.static_ui.draw()?;
+    for label in self.score.iter().chain(&self.s_length) {
+      label.draw()?;
+    }
+
+    self.combo.draw()?;
+    self.time.draw()?;
+    self.split.draw()
+  }
+
+  pub fn print_combo(&mut self, player: usize,
+      multiplier: &u64) -> Result<()> {
+
+    if player > 0 || self.score.len() > 1 {
+      return Ok(());
+    }
+
+    let theme = theme::current();
+    let combo = if *multiplier > 1 {
+      format!("{}{:<2}", theme.glyphs.times, multiplier)
+    }
+    else {
+      "   ".to_string()
+    };
+
+    self.combo.set_message(combo.with(theme.colors.combo).bold());
+    self.combo.draw()
+  }
+
+  pub fn set_keys(&mut self, scheme: KeyScheme, layout: Layout,
+      hold: bool) -> Result<()> {
+    self.static_ui.set_keys(scheme, layout, hold);
+    self.static_ui.draw()
+  }
+
+  pub fn window_origin(&self, window: &PopupWindow) -> Pos {
+    let x = 2 + self.field_size.width
+      .saturating_sub(window.width() as u16 + 2) / 2;
+    let y = 1 + self.field_size.height
+      .saturating_sub(window.height() as u16) / 2;
+
+    Pos::from((x, y))
+  }
+
+  pub fn print_window(&self, window: PopupWindow) -> Result<()> {
+    let origin = self.window_origin(&window);
+    window.origin(origin).draw()
+  }
+
+  pub fn click(&self, pos: Pos) -> Option<Click> {
+    if self.static_ui.pause_button().contains(pos) {
+      return Some(Click::Pause);
+    }
+
+    self.buttons.iter()
+      .find(|(button, _)| button.contains(pos))
+      .map(|(_, click)| *click)
+  }
+
+  pub fn clear_field(&mut self) -> Result<()> {
+    self.buttons.clear();
+    let blank = " ".repeat(self.field_size.width as usize);
+    for y in 1..=self.field_size.height {
+      execute!(out(), cursor::MoveTo(2, y), Print(&blank))?;
+    }
+
+    Ok(())
+  }
+
+  pub fn print_game_over(&mut self, breakdown: &[(String, u64)],
+      buttons: bool) -> Result<()> {
+
+    let name_width = breakdown.iter()
+      .map(|(name, _)| name.chars().count())
+      .max()
+      .unwrap_or(0);
+
+    let mut lines: Vec<String> = breakdown.iter()
+      .map(|(name, value)| format!(
+        "{: <2$} {:>8}", name, value, name_width
+      ))
+      .collect();
+
+    let restart = Button::new(tr!("ui.restart"));
+    let menu = Button::new(tr!("ui.menu"));
+    if buttons {
+      lines.push(String::new());
+      lines.push(format!("{}   {}", restart.get_text(), menu.get_text()));
+    }
+
+    let window = PopupWindow::new(format!(" {} ", tr!("ui.game_over")), lines);
+    let origin = self.window_origin(&window);
+    let row = origin.add_x(2).add_y(window.height() as u16 - 2);
+    self.print_window(window)?;
+
+    if buttons {
+      let menu = menu.origin(row.add_x(restart.width() + 3));
+      let restart = restart.origin(row);
+      self.buttons = vec![(restart, Click::Restart), (menu, Click::Menu)];
+      for (button, _) in &self.buttons {
+        button.draw()?;
+      }
+    }
+
+    Ok(())
+  }
+
+  pub fn print_stats(&mut self, player: usize,
+      score: &u64, s_length: &u16) -> Result<()> {
+
+    if player >= self.score.len() {
+      return Ok(());
+    }
+
+    let color = self.colors[player];
+    self.score[player].set_message(
+      score.to_string().with(color).bold()
     );
-    self.s_length.set_message(
-      s_length.to_string().with(Magenta).bold()
+    self.s_length[player].set_message(
+      s_length.to_string().with(color).bold()
     );
 
-    self.score.draw()?;
-    self.s_length.draw()
+    self.score[player].draw()?;
+    self.s_length[player].draw()
   }
 
-  pub fn print_time(&mut self, time: &f64) -> Result<()> {
-    let minutes = (time / 60.0).floor() as u64;
-    let seconds = time % 60.0;
+  pub fn print_split(&mut self, split: Option<&Split>) -> Result<()> {
+    self.split.set_message(split_message(split));
+    self.split.draw()
+  }
 
+  pub fn print_time(&mut self, time: &f64) -> Result<()> {
     self.time.set_message(
-      format!(
-        "{}м{:.1}с ",
-        minutes,
-        seconds
-      ).with(Magenta).bold()
+      format!("{} ", format_time(*time)).with(theme::current().colors.accent).bold()
     );
 
     self.time.draw()
@@ -141,8 +354,39 @@ impl UI {
   }
 
   pub fn disable_raw_mode(&self) {
-    execute!(stdout(), cursor::Show).unwrap();
+    execute!(out(), DisableMouseCapture, cursor::Show).unwrap();
     disable_raw_mode()
       .expect("Could not disable raw mode");
   }
-}
\ No newline at end of file
+}
+
+fn split_message(split: Option<&Split>) -> StyledContent<String> {
+  let theme = theme::current();
+  let colors = theme.colors;
+  let (text, color) = match split {
+    Some(Split { index, delta: Some(delta), .. }) => (
+      format!(" {} {} {:+.3} ", tr!("ui.split"), index, delta),
+      if *delta <= 0.0 { colors.ahead } else { colors.behind }
+    ),
+    Some(Split { index, segment, .. }) => (
+      format!(" {} {} {:.3} ", tr!("ui.split"), index, segment),
+      colors.accent
+    ),
+    None => (String::new(), colors.accent)
+  };
+  let line = theme.glyphs.horizontal.to_string()
+    .repeat(SPLIT_WIDTH.saturating_sub(text.chars().count()));
+
+  format!("{}{}", text.with(color).bold(), line.with(colors.text).bold()).stylize()
+}
+
+fn value_x(field_width: u16, label: &str) -> u16 {
+  field_width + 6 + tr!(label).chars().count() as u16
+}
+
+pub fn format_time(time: f64) -> String {
+  let minutes = (time / 60.0).floor() as u64;
+  let seconds = format!("{:.3}", time % 60.0);
+
+  tr!("ui.time_value", minutes, seconds)
+}
@@ -0,0 +1,195 @@
+use std::{
+  fs::File,
+  io::{self, BufWriter, Write, stdout},
+  path::Path,
+  sync::Mutex,
+  time::{Duration, Instant, SystemTime, UNIX_EPOCH}
+};
+
+use crossterm::terminal;
+
+use crate::json::{Object, ToJson};
+use crate::ui::dimensions::Size;
+
+static RECORDER: Mutex<Option<Recorder>> = Mutex::new(None);
+
+enum Timeline {
+  Real(Instant),
+  Ticks(Duration)
+}
+
+struct Recorder {
+  file: Box<dyn Write + Send>,
+  size: Size,
+  timeline: Timeline,
+  pending: Vec<u8>
+}
+
+impl Recorder {
+  fn new(mut file: Box<dyn Write + Send>, size: Size,
+      headless: bool) -> io::Result<Self> {
+
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)
+      .map_or(0, |time| time.as_secs());
+    let header = Object::new()
+      .field("version", &2u64)
+      .field("width", &size.width)
+      .field("height", &size.height)
+      .field("timestamp", &timestamp)
+      .field("env", &Object::new().field("TERM", "xterm-256color"));
+    writeln!(file, "{}", header.to_json())?;
+
+    let timeline = match headless {
+      true  => Timeline::Ticks(Duration::ZERO),
+      false => Timeline::Real(Instant::now())
+    };
+
+    Ok(Self { file, size, timeline, pending: Vec::new() })
+  }
+
+  fn is_headless(&self) -> bool {
+    matches!(self.timeline, Timeline::Ticks(_))
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    if self.pending.is_empty() {
+      return Ok(());
+    }
+
+    let time = match self.timeline {
+      Timeline::Real(start) => start.elapsed(),
+      Timeline::Ticks(time) => time
+    };
+    let data = String::from_utf8_lossy(&self.pending).into_owned();
+    self.pending.clear();
+
+    writeln!(
+      self.file, "[{}, \"o\", {}]",
+      time.as_secs_f64().to_json(), data.to_json()
+    )?;
+    self.file.flush()
+  }
+}
+
+pub struct Output;
+
+impl Write for Output {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    let mut recorder = RECORDER.lock().unwrap();
+    if let Some(recorder) = recorder.as_mut() {
+      recorder.pending.extend_from_slice(buf);
+      if recorder.is_headless() {
+        return Ok(buf.len());
+      }
+    }
+
+    stdout().write_all(buf)?;
+    Ok(buf.len())
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    let mut recorder = RECORDER.lock().unwrap();
+    match recorder.as_mut() {
+      Some(recorder) if recorder.is_headless() => recorder.flush(),
+      Some(recorder) => {
+        recorder.flush()?;
+        stdout().flush()
+      },
+      None => stdout().flush()
+    }
+  }
+}
+
+pub fn out() -> Output {
+  Output
+}
+
+pub fn start(path: &Path, size: Size, headless: bool) -> io::Result<()> {
+  let file = BufWriter::new(File::create(path)?);
+  record(Box::new(file), size, headless)
+}
+
+fn record(file: Box<dyn Write + Send>, size: Size,
+    headless: bool) -> io::Result<()> {
+
+  *RECORDER.lock().unwrap() = Some(Recorder::new(file, size, headless)?);
+  Ok(())
+}
+
+pub fn finish() -> io::Result<()> {
+  match RECORDER.lock().unwrap().take() {
+    Some(mut recorder) => {
+      recorder.flush()?;
+      recorder.file.flush()
+    },
+    None => Ok(())
+  }
+}
+
+pub fn advance(time: Duration) {
+  if let Some(Recorder { timeline: Timeline::Ticks(now), .. }) =
+      RECORDER.lock().unwrap().as_mut() {
+    *now += time;
+  }
+}
+
+pub fn is_headless() -> bool {
+  RECORDER.lock().unwrap().as_ref().is_some_and(Recorder::is_headless)
+}
+
+pub fn size() -> io::Result<(u16, u16)> {
+  match RECORDER.lock().unwrap().as_ref() {
+    Some(recorder) if recorder.is_headless() =>
+      Ok((recorder.size.width, recorder.size.height)),
+    _ => terminal::size()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{
+    io::{self, Write},
+    sync::{Arc, Mutex},
+    time::Duration
+  };
+
+  use crossterm::{cursor::MoveTo, style::Print, execute};
+
+  use crate::ui::dimensions::Size;
+  use super::{advance, finish, is_headless, out, record, size};
+
+  #[derive(Clone, Default)]
+  struct Shared(Arc<Mutex<Vec<u8>>>);
+
+  impl Write for Shared {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+      self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+      Ok(())
+    }
+  }
+
+  #[test]
+  fn test_headless_cast_timeline() {
+    let file = Shared::default();
+    record(Box::new(file.clone()), Size::from((80, 24)), true).unwrap();
+    assert!(is_headless());
+    assert_eq!(size().unwrap(), (80, 24));
+
+    execute!(out(), MoveTo(2, 1), Print("@")).unwrap();
+    advance(Duration::from_millis(1500));
+    execute!(out(), Print("\"")).unwrap();
+    finish().unwrap();
+    assert!(!is_headless());
+
+    let text = String::from_utf8(file.0.lock().unwrap().clone()).unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+    assert!(lines[0].starts_with(r#"{"version":2,"width":80,"height":24,"#));
+    assert_eq!(&lines[1..], [
+      r#"[0, "o", "\u001b[2;3H@"]"#,
+      r#"[1.5, "o", "\""]"#
+    ]);
+  }
+}
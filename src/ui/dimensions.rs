@@ -1,7 +1,7 @@
 use core::fmt;
 use crossterm::cursor::MoveTo;
 
-#[derive(Copy, Clone, PartialEq)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub struct Pos {
   pub x: u16,
   pub y: u16
@@ -38,7 +38,7 @@ impl From<(u16, u16)> for Pos {
   }
 }
 
-#[derive(Copy, Clone, PartialEq)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub struct Size {
   pub width: u16,
   pub height: u16
@@ -54,6 +54,10 @@ impl Size {
     self.height += height;
     self
   }
+
+  pub fn contains(&self, pos: &Pos) -> bool {
+    pos.x >= 2 && pos.x - 2 < self.width && pos.y >= 1 && pos.y <= self.height
+  }
 }
 
 impl From<Size> for MoveTo {
@@ -91,12 +95,12 @@ mod tests {
   #[test]
   fn test_pos_overlap() {
     let origin = Pos::from((5, 5));
-    assert_eq!(origin.is_overlaps(&Pos::from((5, 5))), true);
-    assert_eq!(origin.is_overlaps(&Pos::from((5, 6))), true);
-    assert_eq!(origin.is_overlaps(&Pos::from((5, 4))), true);
-    assert_eq!(origin.is_overlaps(&Pos::from((6, 5))), true);
-    assert_eq!(origin.is_overlaps(&Pos::from((4, 5))), true);
-    assert_eq!(origin.is_overlaps(&Pos::from((6, 54))), false);
+    assert!(origin.is_overlaps(&Pos::from((5, 5))));
+    assert!(origin.is_overlaps(&Pos::from((5, 6))));
+    assert!(origin.is_overlaps(&Pos::from((5, 4))));
+    assert!(origin.is_overlaps(&Pos::from((6, 5))));
+    assert!(origin.is_overlaps(&Pos::from((4, 5))));
+    assert!(!origin.is_overlaps(&Pos::from((6, 54))));
   }
 
   #[test]
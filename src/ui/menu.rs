@@ -0,0 +1,285 @@
+use std::{
+  io::Result,
+  time::{Duration, Instant}
+};
+
+use crossterm::{
+  cursor,
+  event::{
+    poll, read, Event, KeyCode, KeyEventKind,
+    MouseEvent, MouseEventKind, MouseButton,
+    EnableMouseCapture, DisableMouseCapture
+  },
+  terminal::{self, ClearType, enable_raw_mode, disable_raw_mode},
+  execute
+};
+
+use crate::tr;
+use crate::ui::{
+  Drawable,
+  cast::out,
+  dimensions::Pos,
+  theme,
+  ui_items::PopupWindow
+};
+
+pub enum MenuAction {
+  Select(usize), Idle, Quit
+}
+
+pub struct Menu {
+  title: String,
+  items: Vec<String>,
+  hint: String,
+  selected: usize
+}
+
+impl Menu {
+  pub fn new(title: &str, items: &[&str]) -> Self {
+    Self {
+      title: format!(" {} ", title),
+      items: items.iter().map(|item| item.to_string()).collect(),
+      hint: tr!("menu.hint").to_string(),
+      selected: 0
+    }
+  }
+
+  pub fn hint(mut self, hint: &str) -> Self {
+    self.hint = hint.to_string();
+    self
+  }
+
+  pub fn set_items(&mut self, items: Vec<String>) {
+    self.items = items;
+    self.selected = self.selected.min(self.items.len().saturating_sub(1));
+  }
+
+  pub fn get_selected(&self) -> usize {
+    self.selected
+  }
+
+  pub fn set_selected(&mut self, selected: usize) {
+    if selected < self.items.len() {
+      self.selected = selected;
+    }
+  }
+
+  pub fn select_next(&mut self) {
+    self.selected = (self.selected + 1) % self.items.len();
+  }
+
+  pub fn select_prev(&mut self) {
+    self.selected = (self.selected + self.items.len() - 1) % self.items.len();
+  }
+
+  pub fn key(&mut self, code: KeyCode) -> Option<MenuAction> {
+    match code {
+      KeyCode::Up | KeyCode::Char('w') | KeyCode::Char('ц') =>
+        self.select_prev(),
+      KeyCode::Down | KeyCode::Char('s') | KeyCode::Char('ы') =>
+        self.select_next(),
+      KeyCode::Enter | KeyCode::Char(' ') =>
+        return Some(MenuAction::Select(self.selected)),
+      KeyCode::Esc => return Some(MenuAction::Quit),
+      _ => ()
+    }
+
+    None
+  }
+
+  pub fn item_at(&self, origin: Pos, pos: Pos) -> Option<usize> {
+    let width = self.window().width() as u16 + 2;
+    let i = pos.y.checked_sub(origin.y + 1)? as usize;
+
+    (i < self.items.len() && (origin.x..origin.x + width).contains(&pos.x))
+      .then_some(i)
+  }
+
+  pub fn mouse(&mut self, origin: Pos,
+      event: MouseEvent) -> Option<MenuAction> {
+
+    match event.kind {
+      MouseEventKind::ScrollUp => self.select_prev(),
+      MouseEventKind::ScrollDown => self.select_next(),
+      MouseEventKind::Down(MouseButton::Left) => {
+        let pos = Pos::from((event.column, event.row));
+        let i = self.item_at(origin, pos)?;
+        self.selected = i;
+        return Some(MenuAction::Select(i));
+      },
+      _ => ()
+    }
+
+    None
+  }
+
+  pub fn window(&self) -> PopupWindow {
+    let marker = theme::current().glyphs.marker;
+    let mut lines: Vec<String> = self.items.iter().enumerate()
+      .map(|(i, item)| {
+        let marker = if i == self.selected { marker } else { ' ' };
+        format!("{} {}", marker, item)
+      })
+      .collect();
+    lines.push(String::new());
+    lines.push(self.hint.clone());
+
+    PopupWindow::new(self.title.clone(), lines)
+  }
+
+  pub fn run(&mut self, idle: Duration) -> Result<MenuAction> {
+    enable_raw_mode()?;
+    execute!(
+      out(),
+      terminal::Clear(ClearType::All),
+      cursor::Hide,
+      EnableMouseCapture
+    )?;
+
+    let action = self.select(idle);
+
+    execute!(
+      out(),
+      terminal::Clear(ClearType::All),
+      cursor::Show,
+      DisableMouseCapture
+    )?;
+    disable_raw_mode()?;
+    action
+  }
+
+  fn select(&mut self, idle: Duration) -> Result<MenuAction> {
+    let mut last_input = Instant::now();
+    self.draw()?;
+
+    loop {
+      let left = idle.saturating_sub(last_input.elapsed());
+      if left.is_zero() {
+        return Ok(MenuAction::Idle);
+      }
+
+      if !poll(left)? {
+        continue;
+      }
+
+      match read()? {
+        Event::Key(key) if key.kind == KeyEventKind::Press => {
+          last_input = Instant::now();
+
+          if let Some(action) = self.key(key.code) {
+            return Ok(action);
+          }
+        },
+        Event::Mouse(mouse) if mouse.kind != MouseEventKind::Moved => {
+          last_input = Instant::now();
+
+          let origin = centered_origin(&self.window())?;
+          if let Some(action) = self.mouse(origin, mouse) {
+            return Ok(action);
+          }
+        },
+        Event::Resize(..) => {
+          execute!(out(), terminal::Clear(ClearType::All))?;
+        },
+        _ => continue
+      }
+
+      self.draw()?;
+    }
+  }
+}
+
+impl Drawable for Menu {
+  fn draw(&self) -> Result<()> {
+    draw_centered(&self.window())
+  }
+}
+
+pub fn show(window: &PopupWindow) -> Result<()> {
+  enable_raw_mode()?;
+  execute!(
+    out(),
+    terminal::Clear(ClearType::All),
+    cursor::Hide,
+    EnableMouseCapture
+  )?;
+
+  let shown = wait_key(window);
+
+  execute!(
+    out(),
+    terminal::Clear(ClearType::All),
+    cursor::Show,
+    DisableMouseCapture
+  )?;
+  disable_raw_mode()?;
+  shown
+}
+
+fn wait_key(window: &PopupWindow) -> Result<()> {
+  let mut offset = 0;
+  draw_scrolled(window, offset)?;
+
+  loop {
+    let height = terminal::size()?.1 as usize;
+    let last = window.height().saturating_sub(height);
+    let scrollable = last > 0;
+
+    offset = match read()? {
+      Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
+        KeyCode::Up if scrollable => offset.saturating_sub(1),
+        KeyCode::Down if scrollable => (offset + 1).min(last),
+        _ => return Ok(())
+      },
+      Event::Mouse(mouse) => match mouse.kind {
+        MouseEventKind::ScrollUp => offset.saturating_sub(1),
+        MouseEventKind::ScrollDown => (offset + 1).min(last),
+        MouseEventKind::Down(_) => return Ok(()),
+        _ => continue
+      },
+      Event::Resize(..) => offset.min(last),
+      _ => continue
+    };
+
+    execute!(out(), terminal::Clear(ClearType::All))?;
+    draw_scrolled(window, offset)?;
+  }
+}
+
+fn draw_scrolled(window: &PopupWindow, offset: usize) -> Result<()> {
+  let height = terminal::size()?.1 as usize;
+  draw_centered(&window.scrolled(offset, height))
+}
+
+fn centered_origin(window: &PopupWindow) -> Result<Pos> {
+  let (width, height) = terminal::size()?;
+  let x = width.saturating_sub(window.width() as u16 + 2) / 2;
+  let y = height.saturating_sub(window.height() as u16) / 2;
+
+  Ok(Pos::from((x, y)))
+}
+
+fn draw_centered(window: &PopupWindow) -> Result<()> {
+  window.clone().origin(centered_origin(window)?).draw()
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::ui::dimensions::Pos;
+  use super::Menu;
+
+  #[test]
+  fn test_menu_selection_wraps() {
+    let mut menu = Menu::new("Змейка", &["a", "b", "c"]);
+    menu.select_prev();
+    assert_eq!(menu.get_selected(), 2);
+    menu.select_next();
+    menu.select_next();
+    assert_eq!(menu.get_selected(), 1);
+
+    let origin = Pos::from((10, 5));
+    assert_eq!(menu.item_at(origin, Pos::from((12, 8))), Some(2));
+    assert_eq!(menu.item_at(origin, Pos::from((12, 9))), None);
+    assert_eq!(menu.item_at(origin, Pos::from((9, 6))), None);
+  }
+}
@@ -0,0 +1,126 @@
+use std::io::Result;
+
+use crate::snake::Snake;
+use crate::world::{World, Event};
+
+use crate::ui::{
+  UI, Drawable,
+  dimensions::Pos,
+  ui_items::Symbol
+};
+
+pub trait Renderer {
+  fn init(&mut self, world: &World) -> Result<()>;
+  fn render(&mut self, world: &World, events: &[Event]) -> Result<()>;
+}
+
+impl UI {
+  fn redraw_cell(&self, world: &World, pos: &Pos) -> Result<()> {
+    let field = &world.field;
+
+    if field.apple.get_pos() == *pos {
+      return self.draw(&field.apple);
+    }
+
+    if let Some(brick) = field.bricks.iter()
+        .find(|brick| brick.get_pos() == *pos) {
+      return self.draw(brick);
+    }
+
+    if let Some(hazard) = field.hazards.iter()
+        .find(|hazard| hazard.get_pos() == *pos) {
+      return self.draw(hazard);
+    }
+
+    if let Some(portal) = field.portals.iter()
+        .find(|portal| portal.contains(pos)) {
+      return self.draw(portal);
+    }
+
+    if let Some(part) = world.players.iter()
+        .filter(|p| p.is_alive())
+        .flat_map(|p| p.snake.get_parts())
+        .find(|part| part.get_pos() == *pos) {
+      return self.draw(part);
+    }
+
+    self.draw(&Symbol::new(*pos))
+  }
+}
+
+impl Renderer for UI {
+  fn init(&mut self, world: &World) -> Result<()> {
+    for (i, player) in world.players.iter().enumerate() {
+      self.print_stats(
+        i, &player.score.total(),
+        &(player.snake.get_parts().len() as u16 - 1)
+      )?;
+      self.print_combo(i, &player.score.get_multiplier())?;
+      if player.is_alive() {
+        for part in player.snake.get_parts().iter().rev() {
+          self.draw(part)?;
+        }
+      }
+    }
+
+    self.split.draw()?;
+    self.draw(&world.field.apple)?;
+    self.draw_vec(&world.field.bricks)?;
+    self.draw_vec(&world.field.hazards)?;
+    self.draw_vec(&world.field.portals)
+  }
+
+  fn render(&mut self, world: &World, events: &[Event]) -> Result<()> {
+    let mut snakes_moved = false;
+
+    for event in events {
+      match event {
+        Event::SnakeMoved { vacated, .. } => {
+          self.redraw_cell(world, vacated)?;
+          snakes_moved = true;
+        },
+        Event::HazardMoved { index, vacated } => {
+          self.redraw_cell(world, vacated)?;
+          self.draw(&world.field.hazards[*index])?;
+        },
+        Event::AppleEaten { player, .. } => {
+          let player_ref = &world.players[*player];
+          self.print_stats(
+            *player,
+            &player_ref.score.total(),
+            &(player_ref.snake.get_parts().len() as u16 - 1)
+          )?;
+          self.print_combo(*player, &player_ref.score.get_multiplier())?;
+        },
+        Event::ComboExpired { player } => {
+          self.print_combo(
+            *player, &world.players[*player].score.get_multiplier()
+          )?;
+        },
+        Event::FoodRespawned { old_bricks } => {
+          for pos in old_bricks {
+            self.draw(&Symbol::new(*pos))?;
+          }
+          self.draw_vec(&world.field.bricks)?;
+          self.draw_vec(&world.field.hazards)?;
+          self.draw(&world.field.apple)?;
+        },
+        Event::PlayerDied { player, .. } => {
+          if !world.is_over() {
+            for part in world.players[*player].snake.get_parts() {
+              self.redraw_cell(world, &part.get_pos())?;
+            }
+          }
+        }
+      }
+    }
+
+    if snakes_moved {
+      for player in world.players.iter().filter(|p| p.is_alive()) {
+        self.draw::<Snake>(&player.snake)?;
+      }
+    }
+
+    Ok(())
+  }
+}
@@ -1,15 +1,17 @@
-use std::io::{self, Result};
+use std::io::Result;
 
 use crossterm::{
-  terminal,
   cursor::MoveTo,
-  style::{Print, Color::*, Stylize},
+  style::{Print, Stylize},
   execute
 };
 
 use crate::ui::{
+  Drawable,
+  cast::{self, out},
   dimensions::{Pos, Size},
-  ui_items::Label
+  theme,
+  ui_items::{Label, Button}
 };
 
 use crate::food::{
@@ -17,37 +19,64 @@ use crate::food::{
   get_food_with_type
 };
 
+use crate::hazard::Hazard;
+use crate::settings::{KeyScheme, Layout};
+use crate::tr;
+use crate::snake::Direction;
+
 pub struct StaticUI {
   field_size: Size,
-  static_labels: Vec<Label>
+  players: usize,
+  scheme: KeyScheme,
+  layout: Layout,
+  hold: bool
 }
 
 impl StaticUI {
-  pub fn new(field_size: Size) -> Self {
-    let x = field_size.width + 5;
+  pub fn new(field_size: Size, players: usize) -> Self {
     Self {
       field_size,
-      static_labels: vec![
-        Label::new(
-          Pos::from((x, 1)),
-          "Очки:".to_string()
-            .with(Cyan)
-        ),
-        Label::new(
-          Pos::from((x, 2)),
-          "Длина змеи:".to_string()
-            .with(Cyan)
-        ),
-        Label::new(
-          Pos::from((x, 3)),
-          "Время:".to_string()
-            .with(Cyan)
-        )
-      ],
+      players,
+      scheme: KeyScheme::Wasd,
+      layout: Layout::Qwerty,
+      hold: false
     }
   }
 
+  pub fn set_keys(&mut self, scheme: KeyScheme, layout: Layout, hold: bool) {
+    self.scheme = scheme;
+    self.layout = layout;
+    self.hold = hold;
+  }
+
+  fn key_labels(&self, keys: &str) -> String {
+    let accent = theme::current().colors.accent;
+    keys.chars()
+      .map(|key| self.layout.chars(key)[0].to_uppercase().to_string())
+      .collect::<String>()
+      .with(accent)
+      .bold()
+      .to_string()
+  }
+
+  fn print_labels(&self) -> Result<()> {
+    let color = theme::current().colors.text;
+    let x = self.field_size.width + 5;
+
+    for (y, key) in ["ui.score", "ui.length", "ui.time"].iter().enumerate() {
+      Label::new(
+        Pos::from((x, y as u16 + 1)),
+        tr!(key).to_string()
+          .with(color)
+      ).draw()?;
+    }
+
+    Ok(())
+  }
+
   fn print_frame(&self, pos: Pos, size: Size, title: &str) -> Result<()> {
+    let theme = theme::current();
+    let (color, vertical) = (theme.colors.text, theme.glyphs.vertical);
     let title_pos = Pos::from(
       (
         (size.width / 2 + 1) - 
@@ -57,41 +86,43 @@ impl StaticUI {
     );
 
     execute!(
-      io::stdout(),
+      out(),
       MoveTo::from(pos),
-      Print(format!(
-        "╔{:═<1$}╗", "",
-          size.width as usize
-      ).with(Cyan).bold()),
+      Print(theme.frame_top(size.width as usize).with(color).bold()),
     )?;
 
     for y in pos.y + 1..=pos.y + size.height {
       execute!(
-        io::stdout(),
+        out(),
         MoveTo(pos.x, y),
-        Print("║".with(Cyan).bold()),
+        Print(vertical.with(color).bold()),
         MoveTo(pos.x + size.width + 1, y),
-        Print("║".with(Cyan).bold())
+        Print(vertical.with(color).bold())
       )?;
     }
 
     execute!(
-      io::stdout(),
+      out(),
       MoveTo::from(pos.add_y(size.height + 1)),
-      Print(format!(
-        "╚{:═<1$}╝", "",
-          size.width as usize
-      ).with(Cyan).bold()),
+      Print(theme.frame_bottom(size.width as usize).with(color).bold()),
 
       MoveTo::from(title_pos),
-      Print(format!(" {} ", title).with(Magenta))
+      Print(format!(" {} ", title).with(theme.colors.accent))
     )
   }
 
+  pub fn pause_button(&self) -> Button {
+    let text = tr!("ui.pause_button");
+    let width = text.chars().count() as u16 + 4;
+    let x = self.field_size.width + 4 + 22u16.saturating_sub(width) / 2;
+
+    Button::new(text).origin(Pos::from((x, 4)))
+  }
+
   fn print_frames(&self) -> Result<()> {
     self.print_frame(
       Pos::from((1, 0)),
-      self.field_size.clone(),
+      self.field_size,
       self.field_size.to_string().as_str()
     )?;
 
@@ -100,77 +131,101 @@ impl StaticUI {
     self.print_frame(
       Pos::from((x, 0)),
       Size::from((20, 3)),
-      "Статистика"
+      tr!("ui.stats")
     )?;
+    self.pause_button().draw()?;
 
-    let terminal_size = terminal::size()?;
+    let terminal_size = cast::size()?;
     self.print_frame(
       Pos::from((x, 5)),
       Size::from((terminal_size.0 - self.field_size.width - 6, 7)),
-      "Инструкция"
+      tr!("ui.help")
     )
   }
 
+  fn print_line(&self, y: u16, text: &str, args: &[String]) -> Result<()> {
+    let colors = theme::current().colors;
+    let mut args = args.iter();
+    let width = cast::size()?.0.saturating_sub(self.field_size.width + 6);
+    execute!(
+      out(),
+      MoveTo(self.field_size.width + 5, y),
+      Print(" ".repeat(width as usize)),
+      MoveTo(self.field_size.width + 5, y)
+    )?;
+
+    for (i, part) in text.split('*').enumerate() {
+      if i % 2 == 1 {
+        execute!(out(), Print(part.with(colors.accent).bold()))?;
+        continue;
+      }
+
+      for (j, piece) in part.split("{}").enumerate() {
+        if j > 0 {
+          execute!(out(), Print(args.next().map_or("", String::as_str)))?;
+        }
+        execute!(out(), Print(piece.with(colors.text)))?;
+      }
+    }
+
+    Ok(())
+  }
+
   fn print_help(&self) -> Result<()> {
-    let field_size = &self.field_size;
     let green_appl = get_food_with_type(FoodType::GreenApple);
     let gold_appl  = get_food_with_type(FoodType::GoldApple);
     let brick      = get_food_with_type(FoodType::Brick);
-    let x = field_size.width + 5;
-
-    execute!(
-      io::stdout(),
-      MoveTo(x, 6),
-      Print("Клавиши для перемещения - ".with(Cyan)),
-      Print("WASD".with(Magenta).bold()),
-      Print(" или ".with(Cyan)),
-      Print("стрелки".with(Magenta)),
-      Print(". ".with(Cyan)),
-      MoveTo(x, 7),
-      Print("B".with(Magenta).bold()),
-      Print(" - переключает режим ускорения.".with(Cyan)),
-      MoveTo(x, 8),
-      Print("P".with(Magenta).bold()),
-      Print(" - пауза. ".with(Cyan)),
-      Print("ESC".with(Magenta).bold()),
-      Print(" для выхода. ".with(Cyan)),
-      MoveTo(x, 10),
-      Print("Яблоки ".with(Cyan)),
-      Print(format!(
-        "{} {} {} {} {} {}",
-          green_appl.get_symbol(),
-          gold_appl.get_symbol(),
-          "различных цветов добавляют".with(Cyan),
-          green_appl.get_value()
-            .to_string()
-            .with(green_appl.get_symbol().color),
-          "и".with(Cyan),
-          gold_appl.get_value()
-            .to_string()
-            .with(gold_appl.get_symbol().color)
-        )),
-      MoveTo(x, 11),
-      Print(
-        "очков соответственно. Игра заканчивается когда"
-        .with(Cyan)),
-      MoveTo(x, 12),
-      Print("Змея".with(DarkGreen)),
-      Print(" ест саму себя или кирпич ".with(Cyan)),
-      Print(brick.get_symbol()),
-      Print(" .".with(Cyan)),
-    )
+    let origin     = Pos::from((0, 0));
+    let hazards    = [
+      Hazard::patrol(origin, Vec::new()),
+      Hazard::creature(origin),
+      Hazard::rock(origin, Direction::Down)
+    ];
+    let theme      = theme::current();
+    let colors     = theme.colors;
+    let keys = self.key_labels(&self.scheme.name().to_lowercase());
+    let boost_head = theme.glyphs.boost_head.with(colors.boost).to_string();
+
+    let two = self.players > 1;
+    let controls = if two { "help.two_players" } else { "help.move" };
+    let boost = match (two, self.hold, theme.shapes) {
+      (true, true, true)    => "help.two_boost_hold_shapes",
+      (true, true, false)   => "help.two_boost_hold",
+      (true, false, true)   => "help.two_boost_shapes",
+      (true, false, false)  => "help.two_boost",
+      (false, true, true)   => "help.boost_hold_shapes",
+      (false, true, false)  => "help.boost_hold",
+      (false, false, true)  => "help.boost_shapes",
+      (false, false, false) => "help.boost"
+    };
+
+    self.print_line(6, tr!(controls), &[keys])?;
+    self.print_line(7, tr!(boost), &[self.key_labels("b"), boost_head])?;
+
+    self.print_line(8, tr!("help.pause"), &[self.key_labels("p")])?;
+    self.print_line(9, tr!("help.save"), &[self.key_labels("q")])?;
+    let apples = if theme.shapes { "help.apples_shapes" } else { "help.apples" };
+    self.print_line(10, tr!(apples), &[
+      green_appl.get_symbol().to_string(),
+      gold_appl.get_symbol().to_string(),
+      green_appl.get_value().to_string()
+        .with(green_appl.get_symbol().color).to_string(),
+      gold_appl.get_value().to_string()
+        .with(gold_appl.get_symbol().color).to_string()
+    ])?;
+    self.print_line(11, tr!("help.apples_end"), &[])?;
+    self.print_line(12, tr!("help.death"), &[
+      tr!("help.snake").with(colors.players[0].1).to_string(),
+      brick.get_symbol().to_string(),
+      hazards.iter().map(|hazard| hazard.get_symbol().to_string()).collect()
+    ])
   }
 }
 
-use crate::ui::Drawable;
-
 impl Drawable for StaticUI {
   fn draw(&self) -> Result<()>{
-    for label in &self.static_labels {
-      label.draw()?;
-    }
-
-    Self::print_frames(&self)?;
-    Self::print_help(&self)
+    Self::print_labels(self)?;
+    Self::print_frames(self)?;
+    Self::print_help(self)
   }
 }
\ No newline at end of file
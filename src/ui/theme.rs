@@ -0,0 +1,604 @@
+use std::{
+  env,
+  sync::{OnceLock, RwLock}
+};
+
+use crossterm::style::Color::{self, *};
+
+use crate::tr;
+
+static THEME: OnceLock<RwLock<Theme>> = OnceLock::new();
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Pieces {
+  pub heads: [char; 4],
+  pub boost_heads: [char; 4],
+  pub horizontal: char,
+  pub vertical: char,
+  pub corners: [char; 4],
+  pub tail: char
+}
+
+const UNICODE_PIECES: Pieces = Pieces {
+  heads: ['▲', '▼', '◀', '▶'],
+  boost_heads: ['▲', '▼', '◀', '▶'],
+  horizontal: '━',
+  vertical: '┃',
+  corners: ['┏', '┓', '┗', '┛'],
+  tail: '◆'
+};
+
+const ASCII_PIECES: Pieces = Pieces {
+  heads: ['^', 'v', '<', '>'],
+  boost_heads: ['^', 'v', '<', '>'],
+  horizontal: '=',
+  vertical: '|',
+  corners: ['+', '+', '+', '+'],
+  tail: 'o'
+};
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Glyphs {
+  pub head: char,
+  pub boost_head: char,
+  pub body: char,
+  pub pieces: Pieces,
+  pub green_apple: char,
+  pub gold_apple: char,
+  pub brick: char,
+  pub patrol: char,
+  pub creature: char,
+  pub rock: char,
+  pub portal: char,
+  pub corners: [char; 4],
+  pub horizontal: char,
+  pub vertical: char,
+  pub marker: char,
+  pub times: char
+}
+
+const UNICODE: Glyphs = Glyphs {
+  head: '◇',
+  boost_head: '◇',
+  body: '◆',
+  pieces: UNICODE_PIECES,
+  green_apple: '◉',
+  gold_apple: '◉',
+  brick: '▬',
+  patrol: '■',
+  creature: '✱',
+  rock: '●',
+  portal: '◎',
+  corners: ['╔', '╗', '╚', '╝'],
+  horizontal: '═',
+  vertical: '║',
+  marker: '▶',
+  times: '×'
+};
+
+const ASCII: Glyphs = Glyphs {
+  head: '@',
+  boost_head: '@',
+  body: 'o',
+  pieces: ASCII_PIECES,
+  green_apple: '*',
+  gold_apple: '*',
+  brick: '#',
+  patrol: 'X',
+  creature: '&',
+  rock: 'O',
+  portal: '%',
+  corners: ['+', '+', '+', '+'],
+  horizontal: '-',
+  vertical: '|',
+  marker: '>',
+  times: 'x'
+};
+
+const UNICODE_SHAPES: Glyphs = Glyphs {
+  boost_head: '◈',
+  pieces: Pieces {
+    boost_heads: ['△', '▽', '◁', '▷'],
+    ..UNICODE_PIECES
+  },
+  gold_apple: '★',
+  ..UNICODE
+};
+
+const ASCII_SHAPES: Glyphs = Glyphs {
+  boost_head: 'A',
+  pieces: Pieces {
+    boost_heads: ['A', 'V', '{', '}'],
+    ..ASCII_PIECES
+  },
+  gold_apple: '$',
+  ..ASCII
+};
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Colors {
+  pub text: Color,
+  pub accent: Color,
+  pub popup: Color,
+  pub combo: Color,
+  pub ahead: Color,
+  pub behind: Color,
+  pub boost: Color,
+  pub green_apple: Color,
+  pub gold_apple: Color,
+  pub brick: Color,
+  pub patrol: Color,
+  pub creature: Color,
+  pub rock: Color,
+  pub portals: [Color; 4],
+  pub players: [(Color, Color); 4]
+}
+
+const CLASSIC: Colors = Colors {
+  text: Cyan,
+  accent: Magenta,
+  popup: DarkRed,
+  combo: Yellow,
+  ahead: Green,
+  behind: Red,
+  boost: Cyan,
+  green_apple: Green,
+  gold_apple: Yellow,
+  brick: Red,
+  patrol: Red,
+  creature: Magenta,
+  rock: DarkGrey,
+  portals: [Blue, Yellow, Magenta, Cyan],
+  players: [(Green, DarkGreen), (Blue, DarkBlue), (Yellow, DarkYellow), (White, Grey)]
+};
+
+const OCEAN: Colors = Colors {
+  text: Blue,
+  accent: Cyan,
+  popup: DarkBlue,
+  combo: White,
+  ahead: Green,
+  behind: Magenta,
+  boost: Magenta,
+  green_apple: Green,
+  gold_apple: Yellow,
+  brick: DarkRed,
+  patrol: Red,
+  creature: Magenta,
+  rock: Grey,
+  portals: [Cyan, White, Magenta, Green],
+  players: [(Cyan, DarkCyan), (Magenta, DarkMagenta), (Green, DarkGreen), (White, Grey)]
+};
+
+const RETRO: Colors = Colors {
+  text: DarkGreen,
+  accent: Green,
+  popup: Green,
+  combo: White,
+  ahead: Green,
+  behind: White,
+  boost: White,
+  green_apple: Green,
+  gold_apple: White,
+  brick: DarkGreen,
+  patrol: Green,
+  creature: White,
+  rock: DarkGreen,
+  portals: [White, Green, White, Green],
+  players: [(Green, DarkGreen), (White, Grey), (Green, Grey), (White, DarkGreen)]
+};
+
+const DEUTERANOPIA: Colors = Colors {
+  text: Blue,
+  accent: Yellow,
+  popup: DarkYellow,
+  combo: Yellow,
+  ahead: Blue,
+  behind: Yellow,
+  boost: White,
+  green_apple: Blue,
+  gold_apple: Yellow,
+  brick: DarkGrey,
+  patrol: White,
+  creature: Magenta,
+  rock: Grey,
+  portals: [Cyan, Yellow, White, Blue],
+  players: [(Blue, DarkBlue), (Yellow, DarkYellow), (White, Grey), (Cyan, DarkCyan)]
+};
+
+const PROTANOPIA: Colors = Colors {
+  text: Cyan,
+  accent: Yellow,
+  popup: Blue,
+  combo: Yellow,
+  ahead: Blue,
+  behind: Yellow,
+  boost: White,
+  green_apple: Cyan,
+  gold_apple: Yellow,
+  brick: Grey,
+  patrol: White,
+  creature: Blue,
+  rock: DarkGrey,
+  portals: [Cyan, Yellow, White, Blue],
+  players: [(Blue, DarkBlue), (Yellow, DarkYellow), (White, Grey), (Cyan, DarkCyan)]
+};
+
+const TRITANOPIA: Colors = Colors {
+  text: Cyan,
+  accent: Red,
+  popup: Red,
+  combo: Magenta,
+  ahead: Cyan,
+  behind: Red,
+  boost: White,
+  green_apple: Cyan,
+  gold_apple: Magenta,
+  brick: DarkGrey,
+  patrol: Red,
+  creature: White,
+  rock: Grey,
+  portals: [Red, Cyan, White, Magenta],
+  players: [(Red, DarkRed), (Cyan, DarkCyan), (White, Grey), (Magenta, DarkMagenta)]
+};
+
+const HIGH_CONTRAST: Colors = Colors {
+  text: White,
+  accent: Yellow,
+  popup: White,
+  combo: Yellow,
+  ahead: White,
+  behind: Yellow,
+  boost: Yellow,
+  green_apple: White,
+  gold_apple: Yellow,
+  brick: White,
+  patrol: Yellow,
+  creature: White,
+  rock: Grey,
+  portals: [Yellow, White, Cyan, Yellow],
+  players: [(White, Grey), (Yellow, DarkYellow), (Cyan, DarkCyan), (White, DarkGrey)]
+};
+
+const MONO: Colors = Colors {
+  text: Reset,
+  accent: Reset,
+  popup: Reset,
+  combo: Reset,
+  ahead: Reset,
+  behind: Reset,
+  boost: Reset,
+  green_apple: Reset,
+  gold_apple: Reset,
+  brick: Reset,
+  patrol: Reset,
+  creature: Reset,
+  rock: Reset,
+  portals: [Reset; 4],
+  players: [(Reset, Reset); 4]
+};
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Palette {
+  Classic, Ocean, Retro,
+  Deuteranopia, Protanopia, Tritanopia, HighContrast,
+  Mono
+}
+
+impl Palette {
+  pub const ALL: [Palette; 8] = [
+    Palette::Classic, Palette::Ocean, Palette::Retro,
+    Palette::Deuteranopia, Palette::Protanopia, Palette::Tritanopia,
+    Palette::HighContrast, Palette::Mono
+  ];
+
+  pub fn key(&self) -> &'static str {
+    match self {
+      Palette::Classic => "classic",
+      Palette::Ocean   => "ocean",
+      Palette::Retro   => "retro",
+      Palette::Deuteranopia => "deuteranopia",
+      Palette::Protanopia   => "protanopia",
+      Palette::Tritanopia   => "tritanopia",
+      Palette::HighContrast => "high-contrast",
+      Palette::Mono    => "mono"
+    }
+  }
+
+  pub fn name(&self) -> &'static str {
+    match self {
+      Palette::Classic => tr!("theme.classic"),
+      Palette::Ocean   => tr!("theme.ocean"),
+      Palette::Retro   => tr!("theme.retro"),
+      Palette::Deuteranopia => tr!("theme.deuteranopia"),
+      Palette::Protanopia   => tr!("theme.protanopia"),
+      Palette::Tritanopia   => tr!("theme.tritanopia"),
+      Palette::HighContrast => tr!("theme.high_contrast"),
+      Palette::Mono    => tr!("theme.mono")
+    }
+  }
+
+  pub fn colors(&self) -> Colors {
+    match self {
+      Palette::Classic => CLASSIC,
+      Palette::Ocean   => OCEAN,
+      Palette::Retro   => RETRO,
+      Palette::Deuteranopia => DEUTERANOPIA,
+      Palette::Protanopia   => PROTANOPIA,
+      Palette::Tritanopia   => TRITANOPIA,
+      Palette::HighContrast => HIGH_CONTRAST,
+      Palette::Mono    => MONO
+    }
+  }
+
+  pub fn has_shapes(&self) -> bool {
+    !matches!(self, Palette::Classic | Palette::Ocean | Palette::Retro)
+  }
+
+  pub fn next(&self) -> Self {
+    match self {
+      Palette::Classic => Palette::Ocean,
+      Palette::Ocean   => Palette::Retro,
+      Palette::Retro   => Palette::Deuteranopia,
+      Palette::Deuteranopia => Palette::Protanopia,
+      Palette::Protanopia   => Palette::Tritanopia,
+      Palette::Tritanopia   => Palette::HighContrast,
+      Palette::HighContrast => Palette::Mono,
+      Palette::Mono    => Palette::Classic
+    }
+  }
+
+  pub fn from_key(key: &str) -> Option<Self> {
+    Self::ALL.into_iter().find(|palette| palette.key() == key)
+  }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum GlyphSet {
+  Auto, Unicode, Ascii
+}
+
+impl GlyphSet {
+  pub const ALL: [GlyphSet; 3] = [GlyphSet::Auto, GlyphSet::Unicode, GlyphSet::Ascii];
+
+  pub fn key(&self) -> &'static str {
+    match self {
+      GlyphSet::Auto    => "auto",
+      GlyphSet::Unicode => "unicode",
+      GlyphSet::Ascii   => "ascii"
+    }
+  }
+
+  pub fn name(&self) -> &'static str {
+    match self {
+      GlyphSet::Auto    => tr!("glyphs.auto"),
+      GlyphSet::Unicode => tr!("glyphs.unicode"),
+      GlyphSet::Ascii   => tr!("glyphs.ascii")
+    }
+  }
+
+  pub fn glyphs(&self, shapes: bool) -> Glyphs {
+    let unicode = match self {
+      GlyphSet::Auto    => unicode_supported(),
+      GlyphSet::Unicode => true,
+      GlyphSet::Ascii   => false
+    };
+
+    match (unicode, shapes) {
+      (true, false)  => UNICODE,
+      (true, true)   => UNICODE_SHAPES,
+      (false, false) => ASCII,
+      (false, true)  => ASCII_SHAPES
+    }
+  }
+
+  pub fn next(&self) -> Self {
+    match self {
+      GlyphSet::Auto    => GlyphSet::Unicode,
+      GlyphSet::Unicode => GlyphSet::Ascii,
+      GlyphSet::Ascii   => GlyphSet::Auto
+    }
+  }
+
+  pub fn from_key(key: &str) -> Option<Self> {
+    Self::ALL.into_iter().find(|set| set.key() == key)
+  }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Skin {
+  Plain, Directional, Gradient
+}
+
+impl Skin {
+  pub const ALL: [Skin; 3] = [Skin::Plain, Skin::Directional, Skin::Gradient];
+
+  pub fn key(&self) -> &'static str {
+    match self {
+      Skin::Plain       => "plain",
+      Skin::Directional => "directional",
+      Skin::Gradient    => "gradient"
+    }
+  }
+
+  pub fn name(&self) -> &'static str {
+    match self {
+      Skin::Plain       => tr!("skin.plain"),
+      Skin::Directional => tr!("skin.directional"),
+      Skin::Gradient    => tr!("skin.gradient")
+    }
+  }
+
+  pub fn next(&self) -> Self {
+    match self {
+      Skin::Plain       => Skin::Directional,
+      Skin::Directional => Skin::Gradient,
+      Skin::Gradient    => Skin::Plain
+    }
+  }
+
+  pub fn from_key(key: &str) -> Option<Self> {
+    Self::ALL.into_iter().find(|skin| skin.key() == key)
+  }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Theme {
+  pub colors: Colors,
+  pub glyphs: Glyphs,
+  pub shapes: bool,
+  pub skin: Skin
+}
+
+impl Theme {
+  pub fn new(palette: Palette, glyphs: GlyphSet) -> Self {
+    Self::exact(if no_color() { Palette::Mono } else { palette }, glyphs)
+  }
+
+  pub fn exact(palette: Palette, glyphs: GlyphSet) -> Self {
+    let shapes = palette.has_shapes();
+
+    Self {
+      colors: palette.colors(),
+      glyphs: glyphs.glyphs(shapes),
+      shapes,
+      skin: Skin::Plain
+    }
+  }
+
+  pub fn skin(mut self, skin: Skin) -> Self {
+    self.skin = skin;
+    self
+  }
+
+  pub fn player_colors(&self, player: usize) -> (Color, Color) {
+    self.colors.players[player % self.colors.players.len()]
+  }
+
+  pub fn frame_top(&self, width: usize) -> String {
+    self.frame_line(self.glyphs.corners[0], self.glyphs.corners[1], width)
+  }
+
+  pub fn frame_bottom(&self, width: usize) -> String {
+    self.frame_line(self.glyphs.corners[2], self.glyphs.corners[3], width)
+  }
+
+  fn frame_line(&self, left: char, right: char, width: usize) -> String {
+    let line = self.glyphs.horizontal.to_string().repeat(width);
+    format!("{}{}{}", left, line, right)
+  }
+}
+
+impl Default for Theme {
+  fn default() -> Self {
+    Self::new(Palette::Classic, GlyphSet::Auto)
+  }
+}
+
+pub fn current() -> Theme {
+  *theme().read().unwrap()
+}
+
+pub fn set(new_theme: Theme) {
+  *theme().write().unwrap() = new_theme;
+}
+
+fn theme() -> &'static RwLock<Theme> {
+  THEME.get_or_init(|| RwLock::new(Theme::default()))
+}
+
+pub fn blend(from: Color, to: Color, amount: f32) -> Color {
+  let (Some(from), Some(to)) = (rgb(from), rgb(to)) else {
+    return to;
+  };
+
+  let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * amount) as u8;
+  Color::Rgb {
+    r: mix(from.0, to.0),
+    g: mix(from.1, to.1),
+    b: mix(from.2, to.2)
+  }
+}
+
+fn rgb(color: Color) -> Option<(u8, u8, u8)> {
+  match color {
+    Black       => Some((0, 0, 0)),
+    DarkGrey    => Some((128, 128, 128)),
+    Red         => Some((255, 0, 0)),
+    DarkRed     => Some((128, 0, 0)),
+    Green       => Some((0, 255, 0)),
+    DarkGreen   => Some((0, 128, 0)),
+    Yellow      => Some((255, 255, 0)),
+    DarkYellow  => Some((128, 128, 0)),
+    Blue        => Some((0, 0, 255)),
+    DarkBlue    => Some((0, 0, 128)),
+    Magenta     => Some((255, 0, 255)),
+    DarkMagenta => Some((128, 0, 128)),
+    Cyan        => Some((0, 255, 255)),
+    DarkCyan    => Some((0, 128, 128)),
+    White       => Some((255, 255, 255)),
+    Grey        => Some((192, 192, 192)),
+    Rgb { r, g, b } => Some((r, g, b)),
+    _ => None
+  }
+}
+
+pub fn no_color() -> bool {
+  env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+}
+
+pub fn unicode_supported() -> bool {
+  if cfg!(windows) {
+    return true;
+  }
+
+  let term = env::var("TERM").unwrap_or_default();
+  if matches!(term.as_str(), "dumb" | "linux" | "vt100" | "vt220") {
+    return false;
+  }
+
+  ["LC_ALL", "LC_CTYPE", "LANG"].iter()
+    .filter_map(|name| env::var(name).ok())
+    .find(|value| !value.is_empty())
+    .is_some_and(|value| {
+      let value = value.to_lowercase();
+      value.contains("utf-8") || value.contains("utf8")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+  use crossterm::style::Color;
+  use super::{Palette, GlyphSet, Skin, Theme, blend};
+
+  #[test]
+  fn test_theme_keys_and_frames() {
+    for palette in Palette::ALL {
+      assert_eq!(Palette::from_key(palette.key()), Some(palette));
+    }
+    assert_eq!(GlyphSet::from_key("ascii"), Some(GlyphSet::Ascii));
+    assert_eq!(GlyphSet::Ascii.next(), GlyphSet::Auto);
+
+    let theme = Theme::new(Palette::Ocean, GlyphSet::Ascii);
+    assert_eq!(theme.frame_top(3), "+---+");
+    assert_eq!(theme.frame_bottom(1), "+-+");
+    assert_eq!(Palette::Mono.next(), Palette::Classic);
+    assert_eq!(Skin::from_key("gradient"), Some(Skin::Gradient));
+    let mixed = blend(Color::Green, Color::DarkGreen, 0.5);
+    assert_eq!(mixed, Color::Rgb { r: 0, g: 191, b: 0 });
+    assert_eq!(blend(Color::Reset, Color::Reset, 0.5), Color::Reset);
+
+    let glyphs = Theme::new(Palette::Deuteranopia, GlyphSet::Unicode).glyphs;
+    assert_ne!(glyphs.green_apple, glyphs.gold_apple);
+    assert_ne!(glyphs.head, glyphs.boost_head);
+    let glyphs = GlyphSet::Ascii.glyphs(true);
+    let shapes = [
+      glyphs.head, glyphs.boost_head, glyphs.body, glyphs.green_apple,
+      glyphs.gold_apple, glyphs.brick, glyphs.patrol, glyphs.creature,
+      glyphs.rock, glyphs.portal
+    ];
+    for (i, ch) in shapes.iter().enumerate() {
+      assert!(!shapes[i + 1..].contains(ch));
+    }
+  }
+}
@@ -1,5 +1,7 @@
 use crate::ui::{
+  cast::out,
   dimensions::Pos,
+  theme,
   Drawable
 };
 
@@ -15,9 +17,9 @@ use crossterm::{
 };
 
 use core::fmt;
-use std::io::{stdout, Result};
+use std::io::Result;
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, PartialEq)]
 pub struct Symbol {
   pub color: Color,
   pub pos: Pos,
@@ -47,7 +49,7 @@ impl Symbol {
 impl Drawable for Symbol {
   fn draw(&self) -> Result<()> {
     execute!(
-      stdout(),
+      out(),
       MoveTo(self.pos.x, self.pos.y),
       Print(self.ch.with(self.color))
     )
@@ -78,13 +80,52 @@ impl Label {
 impl Drawable for Label {
   fn draw(&self) -> Result<()> {
     execute!(
-      stdout(),
+      out(),
       MoveTo::from(self.pos),
       Print(&self.message)
     )
   }
 }
 
+#[derive(Clone, PartialEq, Debug)]
+pub struct Button {
+  pos: Pos,
+  text: String
+}
+
+impl Button {
+  pub fn new(text: &str) -> Self {
+    Self { pos: Pos::from((0, 0)), text: format!("[ {} ]", text) }
+  }
+
+  pub fn origin(mut self, pos: Pos) -> Self {
+    self.pos = pos;
+    self
+  }
+
+  pub fn get_text(&self) -> &str {
+    &self.text
+  }
+
+  pub fn width(&self) -> u16 {
+    self.text.chars().count() as u16
+  }
+
+  pub fn contains(&self, pos: Pos) -> bool {
+    pos.y == self.pos.y && (self.pos.x..self.pos.x + self.width()).contains(&pos.x)
+  }
+}
+
+impl Drawable for Button {
+  fn draw(&self) -> Result<()> {
+    execute!(
+      out(),
+      MoveTo::from(self.pos),
+      Print(self.text.clone().with(theme::current().colors.accent).bold())
+    )
+  }
+}
+
 pub struct PopupMessage {
   origin: Pos,
   message: String
@@ -98,30 +139,101 @@ impl PopupMessage {
 
 impl Drawable for PopupMessage {
   fn draw(&self) -> Result<()> {
+    let theme = theme::current();
+    let (color, vertical) = (theme.colors.popup, theme.glyphs.vertical);
     let char_count = self.message.chars().count();
 
     execute!(
-      stdout(),
+      out(),
       MoveTo::from(self.origin),
-      Print(format!(
-        "╔{:═<1$}╗", "",
-        char_count + 2
-      ).with(DarkRed).bold()),
+      Print(theme.frame_top(char_count + 2).with(color).bold()),
       MoveTo::from(self.origin.add_y(1)),
       Print(format!(
-        "║{: <1$}║", "",
-        char_count + 2
-      ).with(DarkRed).bold()),
+        "{v}{: <w$}{v}", "",
+        v = vertical, w = char_count + 2
+      ).with(color).bold()),
       MoveTo::from(self.origin.add_y(2)),
-      Print(format!(
-        "╚{:═<1$}╝", "",
-        char_count + 2
-      ).with(DarkRed).bold()),
+      Print(theme.frame_bottom(char_count + 2).with(color).bold()),
 
       MoveTo::from(self.origin.add_x(2).add_y(1)),
-      Print(&self.message.clone().with(DarkRed).bold())
+      Print(&self.message.clone().with(color).bold())
     )?;
 
     Ok(())
   }
-}
\ No newline at end of file
+}
+
+#[derive(Clone)]
+pub struct PopupWindow {
+  origin: Pos,
+  title: String,
+  lines: Vec<String>
+}
+
+impl PopupWindow {
+  pub fn new(title: String, lines: Vec<String>) -> Self {
+    Self { origin: Pos::from((0, 0)), title, lines }
+  }
+
+  pub fn origin(mut self, origin: Pos) -> Self {
+    self.origin = origin;
+    self
+  }
+
+  pub fn width(&self) -> usize {
+    self.lines.iter()
+      .map(|line| line.chars().count())
+      .chain([self.title.chars().count()])
+      .max()
+      .unwrap_or(0) + 2
+  }
+
+  pub fn height(&self) -> usize {
+    self.lines.len() + 2
+  }
+
+  pub fn scrolled(&self, offset: usize, height: usize) -> Self {
+    let lines = self.lines.iter()
+      .skip(offset)
+      .take(height.saturating_sub(2))
+      .cloned()
+      .collect();
+
+    Self { lines, ..self.clone() }
+  }
+}
+
+impl Drawable for PopupWindow {
+  fn draw(&self) -> Result<()> {
+    let theme = theme::current();
+    let (color, vertical) = (theme.colors.popup, theme.glyphs.vertical);
+    let width = self.width();
+    let title_x = self.origin.x +
+      (width - self.title.chars().count()) as u16 / 2;
+
+    execute!(
+      out(),
+      MoveTo::from(self.origin),
+      Print(theme.frame_top(width).with(color).bold()),
+      MoveTo(title_x, self.origin.y),
+      Print(&self.title.clone().with(theme.colors.accent).bold())
+    )?;
+
+    for (i, line) in self.lines.iter().enumerate() {
+      execute!(
+        out(),
+        MoveTo::from(self.origin.add_y(i as u16 + 1)),
+        Print(format!(
+          "{v} {: <w$} {v}", line,
+          v = vertical, w = width - 2
+        ).with(color).bold())
+      )?;
+    }
+
+    execute!(
+      out(),
+      MoveTo::from(self.origin.add_y(self.lines.len() as u16 + 1)),
+      Print(theme.frame_bottom(width).with(color).bold())
+    )
+  }
+}
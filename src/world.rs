@@ -0,0 +1,765 @@
+use rand::{Rng, SeedableRng};
+use crossterm::style::Color;
+
+use crate::ai::{self, Difficulty};
+
+use crate::food::{
+  Food, FoodType,
+  generate_food, get_food_at
+};
+
+use crate::hazard::{
+  Hazard, HazardKind, generate_hazard
+};
+
+use crate::level::Level;
+
+use crate::portal::{
+  Portal, generate_portals
+};
+
+use crate::rng::GameRng;
+use crate::score::Score;
+use crate::snake::{Snake, Direction};
+use crate::tr;
+use crate::ui::{dimensions::{Pos, Size}, theme::Palette};
+
+pub mod input;
+mod save;
+
+pub use input::{InputBuffer, InputMode};
+
+pub const TICK_MS: u64 = 50;
+const SNAKE_TICKS: u16 = 4;
+const SNAKE_BOOST_TICKS: u16 = 3;
+
+pub const MAX_PLAYERS: usize = 4;
+pub const MIN_FIELD_SIZE: (u16, u16) = (10, 5);
+const SPAWN_ATTEMPTS: usize = 100;
+
+pub fn player_colors(player: usize) -> (Color, Color) {
+  Palette::Classic.colors().players[player % MAX_PLAYERS]
+}
+
+#[derive(Clone)]
+pub struct GameOptions {
+  pub level: Option<Level>,
+  pub arcade: bool,
+  pub players: usize,
+  pub bots: Vec<Difficulty>,
+  pub demo: bool,
+  pub seed: Option<u64>
+}
+
+impl Default for GameOptions {
+  fn default() -> Self {
+    Self {
+      level: None, arcade: false, players: 1,
+      bots: Vec::new(), demo: false, seed: None
+    }
+  }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum DeathCause {
+  SelfBite, Brick,
+  Hazard(HazardKind),
+  HeadToHead,
+  Snake(usize)
+}
+
+impl DeathCause {
+  pub fn key(&self) -> &'static str {
+    match self {
+      DeathCause::SelfBite   => "self",
+      DeathCause::Brick      => "brick",
+      DeathCause::HeadToHead => "head_to_head",
+      DeathCause::Snake(_)   => "snake",
+      DeathCause::Hazard(HazardKind::Patrol)   => "patrol",
+      DeathCause::Hazard(HazardKind::Creature) => "creature",
+      DeathCause::Hazard(HazardKind::Rock)     => "rock"
+    }
+  }
+
+  pub fn message(&self) -> &'static str {
+    match self {
+      DeathCause::SelfBite   => tr!("death.self"),
+      DeathCause::Brick      => tr!("death.brick"),
+      DeathCause::HeadToHead => tr!("death.head_to_head"),
+      DeathCause::Snake(_)   => tr!("death.snake"),
+      DeathCause::Hazard(HazardKind::Patrol)   => tr!("death.patrol"),
+      DeathCause::Hazard(HazardKind::Creature) => tr!("death.creature"),
+      DeathCause::Hazard(HazardKind::Rock)     => tr!("death.rock")
+    }
+  }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Outcome {
+  Winner(usize), Draw
+}
+
+pub enum Event {
+  SnakeMoved { player: usize, vacated: Pos },
+  AppleEaten { player: usize, food: FoodType, points: u64 },
+  FoodRespawned { old_bricks: Vec<Pos> },
+  HazardMoved { index: usize, vacated: Pos },
+  ComboExpired { player: usize },
+  PlayerDied { player: usize, cause: DeathCause }
+}
+
+pub struct Field {
+  pub(crate) apple: Box<dyn Food>,
+  pub(crate) bricks: Vec<Box<dyn Food>>,
+  pub(crate) hazards: Vec<Hazard>,
+  pub(crate) portals: Vec<Portal>
+}
+
+impl Field {
+  pub fn get_apple(&self) -> &dyn Food {
+    self.apple.as_ref()
+  }
+
+  pub fn get_bricks(&self) -> &[Box<dyn Food>] {
+    &self.bricks
+  }
+
+  pub fn get_hazards(&self) -> &[Hazard] {
+    &self.hazards
+  }
+
+  pub fn get_portals(&self) -> &[Portal] {
+    &self.portals
+  }
+
+  pub fn has_brick(&self, pos: &Pos) -> bool {
+    self.bricks.iter().any(|brick| brick.get_pos() == *pos)
+  }
+
+  pub fn has_hazard(&self, pos: &Pos) -> bool {
+    self.hazards.iter().any(|hazard| hazard.get_pos() == *pos)
+  }
+
+  pub fn has_portal(&self, pos: &Pos) -> bool {
+    self.portals.iter().any(|portal| portal.contains(pos))
+  }
+}
+
+pub struct Player {
+  pub(crate) snake: Snake,
+  pub(crate) score: Score,
+  pub(crate) boost: bool,
+  pub(crate) death: Option<DeathCause>,
+  pub(crate) bot: Option<Difficulty>,
+  input: InputBuffer,
+  wait: u16
+}
+
+impl Player {
+  fn new(snake: Snake, bot: Option<Difficulty>) -> Self {
+    Self {
+      snake,
+      score: Score::new(),
+      boost: false,
+      death: None,
+      bot,
+      input: InputBuffer::default(),
+      wait: 0
+    }
+  }
+
+  pub fn get_snake(&self) -> &Snake {
+    &self.snake
+  }
+
+  pub fn get_score(&self) -> &Score {
+    &self.score
+  }
+
+  pub fn get_boost(&self) -> bool {
+    self.boost
+  }
+
+  pub fn get_death(&self) -> Option<DeathCause> {
+    self.death
+  }
+
+  pub fn get_bot(&self) -> Option<Difficulty> {
+    self.bot
+  }
+
+  pub fn is_alive(&self) -> bool {
+    self.death.is_none()
+  }
+
+  pub fn push_direction(&mut self, dir: Direction) {
+    self.input.push(dir, self.snake.get_direction());
+  }
+
+  pub fn set_input(&mut self, depth: usize, mode: InputMode) {
+    self.input.configure(depth, mode);
+  }
+
+  pub fn toggle_boost(&mut self) {
+    self.set_boost(!self.boost);
+  }
+
+  pub fn set_boost(&mut self, boost: bool) {
+    self.boost = boost;
+    self.snake.set_boost(boost);
+  }
+
+  fn period(&self) -> u16 {
+    if self.boost { SNAKE_BOOST_TICKS } else { SNAKE_TICKS }
+  }
+}
+
+pub struct World {
+  pub(crate) field_size: Size,
+  pub(crate) field: Field,
+  pub(crate) players: Vec<Player>,
+  pub(crate) tick: u64,
+  pub(crate) seed: u64,
+  rng: GameRng,
+  static_bricks: bool
+}
+
+impl World {
+  pub fn new(field_size: Size, options: &GameOptions) -> Self {
+    let seed = options.seed.unwrap_or_else(rand::random);
+    let mut rng = GameRng::seed_from_u64(seed);
+    let mut players: Vec<Player> = Vec::new();
+
+    let count = (options.players + options.bots.len())
+      .clamp(1, MAX_PLAYERS);
+    let mut blocked: Vec<Pos> = options.level.iter()
+      .flat_map(|level| level.get_cells())
+      .collect();
+    for i in 0..count {
+      let (head, body) = player_colors(i);
+      let snake = spawn_snake(&mut rng, field_size, &blocked)
+        .colors(head, body);
+      blocked.push(snake.get_head_pos());
+
+      let bot = i.checked_sub(options.players)
+        .and_then(|j| options.bots.get(j).copied());
+      players.push(Player::new(snake, bot));
+    }
+
+    let head_pos = players[0].snake.get_head_pos();
+
+    let field = match &options.level {
+      Some(level) => Field {
+        apple: generate_food(&mut rng, &field_size, true, &head_pos),
+        bricks: level.bricks.iter()
+          .map(|pos| get_food_at(FoodType::Brick, *pos))
+          .collect(),
+        hazards: level.hazards.clone(),
+        portals: level.portals.clone()
+      },
+      None => {
+        let density = field_size.width as u64 *
+        field_size.height as u64 / 100;
+
+        let portals = if options.arcade {
+          generate_portals(
+            &mut rng, &field_size, 1 + density as usize / 6, &head_pos
+          )
+        }
+        else {
+          Vec::new()
+        };
+
+        let mut field = Field {
+          apple: generate_food(&mut rng, &field_size, true, &head_pos),
+          bricks: Vec::new(),
+          hazards: Vec::new(),
+          portals
+        };
+
+        while (field.hazards.len() as u64) < density / 4 {
+          let hazard = generate_hazard(&mut rng, &field_size, &head_pos);
+          if !field.has_portal(&hazard.get_pos()) {
+            field.hazards.push(hazard);
+          }
+        }
+
+        field
+      }
+    };
+
+    let mut world = World {
+      field_size,
+      field,
+      players,
+      tick: 0,
+      seed,
+      rng,
+      static_bricks: options.level.is_some()
+    };
+
+    world.respawn_bricks(&head_pos);
+    world.place_apple(&head_pos);
+
+    world
+  }
+
+  pub fn get_field_size(&self) -> Size {
+    self.field_size
+  }
+
+  pub fn get_field(&self) -> &Field {
+    &self.field
+  }
+
+  pub fn get_players(&self) -> &[Player] {
+    &self.players
+  }
+
+  pub fn get_player_mut(&mut self, player: usize) -> &mut Player {
+    &mut self.players[player]
+  }
+
+  pub fn get_tick(&self) -> u64 {
+    self.tick
+  }
+
+  pub fn get_seed(&self) -> u64 {
+    self.seed
+  }
+
+  pub fn is_occupied_by_snake(&self, pos: &Pos) -> bool {
+    self.players.iter().any(|p| p.is_alive() && p.snake.check_pos(pos))
+  }
+
+  pub fn humans(&self) -> usize {
+    self.players.iter().filter(|p| p.bot.is_none()).count()
+  }
+
+  pub fn player_name(&self, player: usize) -> String {
+    match self.players[player].bot {
+      Some(difficulty) => tr!(
+        "name.bot", player + 1 - self.humans(), difficulty.name()
+      ),
+      None => tr!("name.player", player + 1)
+    }
+  }
+
+  pub fn is_over(&self) -> bool {
+    let human_died = self.players.iter()
+      .any(|p| p.bot.is_none() && !p.is_alive());
+    let bots_died = self.players.iter().any(|p| p.bot.is_some()) &&
+      self.players.iter()
+        .filter(|p| p.bot.is_some())
+        .all(|p| !p.is_alive());
+
+    human_died || bots_died
+  }
+
+  pub fn outcome(&self) -> Outcome {
+    let alive: Vec<usize> = (0..self.players.len())
+      .filter(|&i| self.players[i].is_alive())
+      .collect();
+
+    if alive.len() == 1 {
+      return Outcome::Winner(alive[0]);
+    }
+
+    let best = self.players.iter()
+      .map(|p| p.score.total())
+      .max()
+      .unwrap_or(0);
+    let leaders: Vec<usize> = (0..self.players.len())
+      .filter(|&i| self.players[i].score.total() == best)
+      .collect();
+
+    if leaders.len() == 1 {
+      Outcome::Winner(leaders[0])
+    }
+    else {
+      Outcome::Draw
+    }
+  }
+
+  pub fn tick(&mut self) -> Vec<Event> {
+    let mut events = Vec::new();
+    self.tick += 1;
+
+    for (i, player) in self.players.iter_mut().enumerate() {
+      if player.score.expire(self.tick) {
+        events.push(Event::ComboExpired { player: i });
+      }
+    }
+
+    self.steer_bots();
+
+    let mut moved = Vec::new();
+    for (i, player) in self.players.iter_mut().enumerate() {
+      if !player.is_alive() {
+        continue;
+      }
+
+      player.wait += 1;
+      if player.wait < player.period() {
+        continue;
+      }
+      player.wait = 0;
+
+      if let Some(dir) = player.input.pop() {
+        player.snake.set_direction(dir)
+      }
+
+      let vacated = player.snake.update(&self.field.portals);
+      events.push(Event::SnakeMoved { player: i, vacated });
+      moved.push(i);
+    }
+
+    let deaths = self.collisions(&moved);
+    self.kill(deaths, &mut events);
+
+    for &i in &moved {
+      if self.players[i].is_alive() &&
+          self.players[i].snake.check_pos(&self.field.apple.get_pos()) {
+        self.eat(i, &mut events);
+      }
+    }
+
+    if !self.is_over() {
+      self.hazards_update(&mut events);
+    }
+
+    events
+  }
+
+  pub fn will_move(&self, player: usize) -> bool {
+    let player = &self.players[player];
+    player.wait + 1 >= player.period()
+  }
+
+  fn steer_bots(&mut self) {
+    for i in 0..self.players.len() {
+      let player = &self.players[i];
+      let Some(difficulty) = player.bot else { continue };
+
+      if player.is_alive() && self.will_move(i) {
+        let dir = ai::choose_direction(self, i, difficulty);
+        self.players[i].push_direction(dir);
+      }
+    }
+  }
+
+  fn collisions(&self, moved: &[usize]) -> Vec<(usize, DeathCause)> {
+    let mut deaths = Vec::new();
+
+    for &i in moved {
+      let snake = &self.players[i].snake;
+      let head_pos = snake.get_head_pos();
+
+      if snake.check_self_eaten() {
+        deaths.push((i, DeathCause::SelfBite));
+        continue;
+      }
+
+      if self.field.has_brick(&head_pos) {
+        deaths.push((i, DeathCause::Brick));
+        continue;
+      }
+
+      for (j, other) in self.players.iter().enumerate() {
+        if i == j || !other.is_alive() {
+          continue;
+        }
+
+        if other.snake.get_head_pos() == head_pos {
+          deaths.push((i, DeathCause::HeadToHead));
+          break;
+        }
+
+        if other.snake.check_pos(&head_pos) {
+          deaths.push((i, DeathCause::Snake(j)));
+          break;
+        }
+      }
+    }
+
+    deaths.extend(self.hazard_collisions());
+    deaths
+  }
+
+  fn hazard_collisions(&self) -> Vec<(usize, DeathCause)> {
+    let mut deaths = Vec::new();
+
+    for (i, player) in self.players.iter().enumerate() {
+      if !player.is_alive() {
+        continue;
+      }
+
+      if let Some(hazard) = self.field.hazards.iter()
+          .find(|h| player.snake.check_pos(&h.get_pos())) {
+        deaths.push((i, DeathCause::Hazard(hazard.get_kind())));
+      }
+    }
+
+    deaths
+  }
+
+  fn kill(&mut self, deaths: Vec<(usize, DeathCause)>,
+      events: &mut Vec<Event>) {
+
+    for (player, cause) in deaths {
+      if self.players[player].is_alive() {
+        self.players[player].death = Some(cause);
+        events.push(Event::PlayerDied { player, cause });
+      }
+    }
+  }
+
+  fn eat(&mut self, i: usize, events: &mut Vec<Event>) {
+    let player = &mut self.players[i];
+    let food = self.field.apple.get_type();
+    let points = player.score.eat(
+      self.field.apple.get_value(), self.tick, player.boost
+    );
+    player.snake.add_part();
+    events.push(Event::AppleEaten { player: i, food, points });
+
+    let head_pos = player.snake.get_head_pos();
+    let old_bricks = self.field.bricks.iter()
+      .map(|brick| brick.get_pos())
+      .collect();
+
+    self.field.apple = generate_food(
+      &mut self.rng, &self.field_size, true, &head_pos
+    );
+    self.place_apple(&head_pos);
+    if !self.static_bricks {
+      self.field.bricks.clear();
+      self.respawn_bricks(&head_pos);
+    }
+
+    events.push(Event::FoodRespawned { old_bricks });
+  }
+
+  fn place_apple(&mut self, snake_pos: &Pos) {
+    loop {
+      let pos = self.field.apple.get_pos();
+
+      if !self.is_occupied_by_snake(&pos) &&
+          !self.field.has_brick(&pos) && !self.field.has_hazard(&pos) &&
+          !self.field.has_portal(&pos) {
+        break;
+      }
+
+      self.field.apple = generate_food(
+        &mut self.rng, &self.field_size, true, snake_pos
+      );
+    }
+  }
+
+  fn respawn_bricks(&mut self, snake_pos: &Pos) {
+    if self.static_bricks {
+      return;
+    }
+
+    let density = self.field_size.width as usize *
+    self.field_size.height as usize / 100;
+
+    while self.field.bricks.len() < density {
+      let brick = generate_food(&mut self.rng, &self.field_size, false, snake_pos);
+      let pos = brick.get_pos();
+
+      if !self.is_occupied_by_snake(&pos) &&
+          self.field.apple.get_pos() != pos &&
+          !self.field.has_brick(&pos) && !self.field.has_hazard(&pos) &&
+          !self.field.has_portal(&pos) {
+        self.field.bricks.push(brick);
+      }
+    }
+  }
+
+  fn hazards_update(&mut self, events: &mut Vec<Event>) {
+    let target = self.players.iter()
+      .filter(|p| p.is_alive())
+      .map(|p| p.snake.get_head_pos())
+      .next();
+    let mut moved = false;
+
+    for i in 0..self.field.hazards.len() {
+      if !self.field.hazards[i].tick() {
+        continue;
+      }
+
+      let (bricks, portals) = (&self.field.bricks, &self.field.portals);
+      let vacated = self.field.hazards[i].step(
+        &mut self.rng, self.field_size, target.unwrap_or(Pos::from((0, 0))),
+        |pos| bricks.iter().any(|brick| brick.get_pos() == *pos) ||
+          portals.iter().any(|portal| portal.contains(pos))
+      );
+
+      events.push(Event::HazardMoved { index: i, vacated });
+      moved = true;
+    }
+
+    if moved {
+      let deaths = self.hazard_collisions();
+      self.kill(deaths, events);
+    }
+  }
+}
+
+fn spawn_snake(rng: &mut impl Rng, field_size: Size,
+    blocked: &[Pos]) -> Snake {
+
+  for _ in 0..SPAWN_ATTEMPTS {
+    let dir = random_direction(rng);
+    let snake = Snake::new(rng, field_size, dir);
+    if !blocked.contains(&snake.get_head_pos()) {
+      return snake;
+    }
+  }
+
+  let dir = random_direction(rng);
+  let free = (1..=field_size.height)
+    .flat_map(|y| (2..=field_size.width + 1).map(move |x| Pos::from((x, y))))
+    .find(|pos| !blocked.contains(pos));
+
+  match free {
+    Some(pos) => Snake::spawn(field_size, dir, pos),
+    None => Snake::new(rng, field_size, dir)
+  }
+}
+
+pub fn random_direction(rng: &mut impl Rng) -> Direction {
+  match rng.gen_range(0..4) {
+    0 => Direction::Up,
+    1 => Direction::Down,
+    2 => Direction::Left,
+    _ => Direction::Right,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::food::FoodType;
+  use crate::level::Level;
+  use crate::snake::Direction;
+  use crate::testing::{self, place, put_apple, put_brick};
+  use crate::ui::dimensions::{Pos, Size};
+  use super::{World, GameOptions, DeathCause, Outcome, Event};
+
+  fn empty_world(players: usize) -> World {
+    let mut world = testing::empty_world((30, 20), players, Vec::new());
+    put_apple(&mut world, FoodType::GreenApple, Pos::from((30, 18)));
+    world
+  }
+
+  fn run_ticks(world: &mut World, ticks: usize) -> Vec<Event> {
+    (0..ticks).flat_map(|_| world.tick()).collect()
+  }
+
+  #[test]
+  fn test_world_moves_snake() {
+    let mut world = empty_world(1);
+    place(&mut world, 0, Direction::Right, Pos::from((5, 5)));
+
+    let events = run_ticks(&mut world, 4);
+    assert!(events.iter().any(|e| matches!(
+      e, Event::SnakeMoved { player: 0, .. }
+    )));
+    assert!(world.players[0].snake.get_head_pos() == Pos::from((6, 5)));
+
+    world.players[0].push_direction(Direction::Down);
+    run_ticks(&mut world, 4);
+    assert!(world.players[0].snake.get_head_pos() == Pos::from((6, 6)));
+  }
+
+  #[test]
+  fn test_eat_apple() {
+    let mut world = empty_world(1);
+    place(&mut world, 0, Direction::Right, Pos::from((5, 5)));
+    put_apple(&mut world, FoodType::GoldApple, Pos::from((6, 5)));
+
+    let events = run_ticks(&mut world, 4);
+    assert!(events.iter().any(|e| matches!(
+      e, Event::AppleEaten { player: 0, food: FoodType::GoldApple, points: 20 }
+    )));
+    assert_eq!(world.players[0].snake.get_parts().len(), 2);
+    assert!(world.field.apple.get_pos() != Pos::from((6, 5)));
+  }
+
+  #[test]
+  fn test_brick_death() {
+    let mut world = empty_world(1);
+    place(&mut world, 0, Direction::Right, Pos::from((5, 5)));
+    put_brick(&mut world, Pos::from((6, 5)));
+
+    run_ticks(&mut world, 4);
+    assert_eq!(world.players[0].death, Some(DeathCause::Brick));
+    assert!(world.is_over());
+  }
+
+  #[test]
+  fn test_seed_is_reproducible() {
+    let options = GameOptions {
+      seed: Some(17), players: 2, arcade: true, ..GameOptions::default()
+    };
+    let mut a = World::new(Size::from((30, 20)), &options);
+    let mut b = World::new(Size::from((30, 20)), &options);
+    run_ticks(&mut a, 40);
+    run_ticks(&mut b, 40);
+
+    assert!(a.field.apple.get_pos() == b.field.apple.get_pos());
+    for (pa, pb) in a.players.iter().zip(&b.players) {
+      assert!(pa.snake.get_head_pos() == pb.snake.get_head_pos());
+    }
+    for (ha, hb) in a.field.hazards.iter().zip(&b.field.hazards) {
+      assert!(ha.get_pos() == hb.get_pos());
+    }
+  }
+
+  #[test]
+  fn test_head_to_head_is_draw() {
+    let mut world = empty_world(2);
+    place(&mut world, 0, Direction::Right, Pos::from((10, 10)));
+    place(&mut world, 1, Direction::Left, Pos::from((12, 10)));
+
+    run_ticks(&mut world, 4);
+    assert_eq!(world.players[0].death, Some(DeathCause::HeadToHead));
+    assert_eq!(world.players[1].death, Some(DeathCause::HeadToHead));
+    assert_eq!(world.outcome(), Outcome::Draw);
+  }
+
+  #[test]
+  fn test_head_to_body_loses() {
+    let mut world = empty_world(2);
+    place(&mut world, 0, Direction::Down, Pos::from((12, 8)));
+    place(&mut world, 1, Direction::Right, Pos::from((12, 10)));
+    world.players[1].snake.add_part();
+    world.players[1].snake.add_part();
+
+    run_ticks(&mut world, 8);
+    assert_eq!(world.players[0].death, Some(DeathCause::Snake(1)));
+    assert!(world.players[1].is_alive());
+    assert_eq!(world.outcome(), Outcome::Winner(1));
+  }
+
+  #[test]
+  fn test_spawn_beside_full_row() {
+    let wall = (0..12).map(|x| format!("brick {x} 2\n")).collect::<String>();
+    let options = GameOptions {
+      level: Some(Level::parse(&wall).unwrap()),
+      players: 4,
+      seed: Some(testing::SEED),
+      ..GameOptions::default()
+    };
+    let world = World::new(Size::from((12, 6)), &options);
+
+    let heads: Vec<Pos> = world.players.iter()
+      .map(|p| p.snake.get_head_pos())
+      .collect();
+    for (i, head) in heads.iter().enumerate() {
+      assert!(!world.field.has_brick(head));
+      assert!(!heads[..i].contains(head));
+    }
+  }
+}
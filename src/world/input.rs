@@ -0,0 +1,138 @@
+use std::collections::VecDeque;
+
+use crate::snake::Direction;
+use crate::tr;
+
+pub const DEFAULT_DEPTH: usize = 2;
+pub const MAX_DEPTH: usize = 4;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum InputMode {
+  Buffered, Latest
+}
+
+impl InputMode {
+  pub const ALL: [InputMode; 2] = [InputMode::Buffered, InputMode::Latest];
+
+  pub fn key(&self) -> &'static str {
+    match self {
+      InputMode::Buffered => "buffered",
+      InputMode::Latest   => "latest"
+    }
+  }
+
+  pub fn name(&self) -> &'static str {
+    match self {
+      InputMode::Buffered => tr!("input.buffered"),
+      InputMode::Latest   => tr!("input.latest")
+    }
+  }
+
+  pub fn next(&self) -> Self {
+    match self {
+      InputMode::Buffered => InputMode::Latest,
+      InputMode::Latest   => InputMode::Buffered
+    }
+  }
+
+  pub fn from_key(key: &str) -> Option<Self> {
+    Self::ALL.into_iter().find(|mode| mode.key() == key)
+  }
+}
+
+#[derive(Clone, Debug)]
+pub struct InputBuffer {
+  turns: VecDeque<Direction>,
+  depth: usize,
+  mode: InputMode
+}
+
+impl Default for InputBuffer {
+  fn default() -> Self {
+    Self::new(DEFAULT_DEPTH, InputMode::Buffered)
+  }
+}
+
+impl InputBuffer {
+  pub fn new(depth: usize, mode: InputMode) -> Self {
+    Self {
+      turns: VecDeque::new(),
+      depth: depth.clamp(1, MAX_DEPTH),
+      mode
+    }
+  }
+
+  pub fn configure(&mut self, depth: usize, mode: InputMode) {
+    *self = Self {
+      turns: std::mem::take(&mut self.turns),
+      ..Self::new(depth, mode)
+    };
+    self.turns.truncate(self.depth);
+  }
+
+  pub fn push(&mut self, dir: Direction, heading: Direction) {
+    if self.mode == InputMode::Latest {
+      if dir == heading {
+        self.turns.clear();
+      }
+      else if !dir.is_opposite(&heading) {
+        self.turns = VecDeque::from([dir]);
+      }
+      return;
+    }
+
+    let last = self.turns.back().copied().unwrap_or(heading);
+    if self.turns.len() < self.depth && dir != last && !dir.is_opposite(&last) {
+      self.turns.push_back(dir);
+    }
+  }
+
+  pub fn pop(&mut self) -> Option<Direction> {
+    self.turns.pop_front()
+  }
+
+  pub fn turns(&self) -> impl Iterator<Item = &Direction> {
+    self.turns.iter()
+  }
+
+  pub fn restore(&mut self, turns: Vec<Direction>) {
+    self.turns = turns.into();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::snake::Direction::{self, *};
+  use super::{InputBuffer, InputMode};
+
+  fn queued(input: &InputBuffer) -> Vec<Direction> {
+    input.turns().copied().collect()
+  }
+
+  #[test]
+  fn test_turns_follow_queued_heading() {
+    let mut input = InputBuffer::new(2, InputMode::Buffered);
+    input.push(Left, Right);
+    input.push(Right, Right);
+    assert!(queued(&input).is_empty());
+
+    input.push(Up, Right);
+    input.push(Down, Right);
+    input.push(Left, Right);
+    input.push(Down, Right);
+    assert_eq!(queued(&input), [Up, Left]);
+
+    assert_eq!(input.pop(), Some(Up));
+    input.push(Down, Up);
+    assert_eq!(queued(&input), [Left, Down]);
+
+    let mut latest = InputBuffer::new(3, InputMode::Latest);
+    latest.push(Up, Right);
+    latest.push(Left, Right);
+    assert_eq!(queued(&latest), [Up]);
+    latest.push(Down, Right);
+    assert_eq!(queued(&latest), [Down]);
+    latest.push(Right, Right);
+    assert!(queued(&latest).is_empty());
+  }
+}
@@ -0,0 +1,263 @@
+use crate::ai::Difficulty;
+use crate::error::{self, SnakeError};
+use crate::food::get_food_at;
+use crate::hazard::Hazard;
+use crate::portal::Portal;
+use crate::rng::GameRng;
+use crate::score::Score;
+use crate::snake::{Snake, Direction};
+use crate::tr;
+use crate::ui::dimensions::Size;
+
+use crate::snapshot::{
+  pos_token, parse_pos, dir_token, food_token, parse_food,
+  hazard_token, parse_hazard, death_token, parse_death
+};
+
+use super::{World, Field, Player, FoodType, player_colors};
+
+fn path_token(path: &[Direction]) -> String {
+  if path.is_empty() {
+    "-".to_string()
+  }
+  else {
+    path.iter().map(|dir| dir_token(*dir)).collect()
+  }
+}
+
+fn parse_path(word: &str) -> Option<Vec<Direction>> {
+  if word == "-" {
+    return Some(Vec::new());
+  }
+
+  word.chars().map(|ch| Direction::try_from(ch).ok()).collect()
+}
+
+fn parse_player(args: &[&str], field_size: Size, index: usize) -> Option<Player> {
+  let [bot, death, boost, dir, sequence, wait, score, parts @ ..] = args
+    else { return None };
+  let parts = parts.iter()
+    .map(|w| parse_pos(w))
+    .collect::<Option<Vec<_>>>()
+    .filter(|parts| !parts.is_empty())
+    .filter(|parts| parts.iter().all(|pos| field_size.contains(pos)))?;
+
+  let (head, body) = player_colors(index);
+  let dir = Direction::try_from(dir.chars().next()?).ok()?;
+  let bot = match *bot {
+    "-" => None,
+    name => Some(Difficulty::try_from(name).ok()?)
+  };
+  let mut player = Player::new(
+    Snake::from_parts(field_size, dir, &parts).colors(head, body), bot
+  );
+
+  player.death = parse_death(death)?;
+  player.boost = *boost == "1";
+  player.snake.set_boost(player.boost);
+  player.input.restore(parse_path(sequence)?);
+  player.wait = wait.parse().ok()?;
+  player.score = Score::decode(score)?;
+
+  Some(player)
+}
+
+impl World {
+  pub fn encode(&self) -> String {
+    let field = &self.field;
+    let mut lines = vec![
+      format!("field {} {}", self.field_size.width, self.field_size.height),
+      format!("tick {}", self.tick),
+      format!("seed {}", self.seed),
+      format!("rng {}", self.rng.get_state()),
+      format!("static {}", self.static_bricks as u8),
+      format!("apple {} {}",
+        food_token(field.apple.get_type()), pos_token(&field.apple.get_pos()))
+    ];
+
+    let bricks: Vec<String> = field.bricks.iter()
+      .map(|brick| pos_token(&brick.get_pos()))
+      .collect();
+    lines.push(format!("bricks {}", bricks.join(" ")).trim_end().to_string());
+
+    for hazard in &field.hazards {
+      lines.push(format!("hazard {} {} {} {} {}",
+        hazard_token(hazard.get_kind()), pos_token(&hazard.get_pos()),
+        hazard.get_step(), hazard.get_wait(), path_token(hazard.get_path())));
+    }
+
+    for portal in &field.portals {
+      let [a, b] = portal.get_ends();
+      lines.push(format!("portal {} {}", pos_token(&a), pos_token(&b)));
+    }
+
+    for player in &self.players {
+      let sequence: Vec<Direction> = player.input.turns().copied().collect();
+      let parts: Vec<String> = player.snake.get_parts().iter()
+        .map(|part| pos_token(&part.get_pos()))
+        .collect();
+
+      lines.push(format!("player {} {} {} {} {} {} {} {}",
+        player.bot.map_or("-", |bot| bot.key()),
+        death_token(player.death),
+        player.boost as u8,
+        dir_token(player.snake.get_direction()),
+        path_token(&sequence),
+        player.wait,
+        player.score.encode(),
+        parts.join(" ")));
+    }
+
+    lines.join("\n") + "\n"
+  }
+
+  pub fn decode(source: &str) -> error::Result<World> {
+    let mut field_size = None;
+    let mut tick = 0;
+    let mut seed = 0;
+    let mut rng = GameRng::from_state(0);
+    let mut static_bricks = false;
+    let mut apple = None;
+    let mut bricks = Vec::new();
+    let mut hazards = Vec::new();
+    let mut portals = Vec::new();
+    let mut players = Vec::new();
+
+    for (i, line) in source.lines().enumerate() {
+      let words: Vec<&str> = line.split_whitespace().collect();
+      let Some((&keyword, args)) = words.split_first() else { continue };
+
+      let parsed = match keyword {
+        "field" => (|| {
+          let [w, h] = args else { return None };
+          field_size = Some(Size::from((w.parse().ok()?, h.parse().ok()?)));
+          Some(())
+        })(),
+        "tick" => args.first()
+          .and_then(|w| w.parse().ok())
+          .map(|value| tick = value),
+        "seed" => args.first()
+          .and_then(|w| w.parse().ok())
+          .map(|value| seed = value),
+        "rng" => args.first()
+          .and_then(|w| w.parse().ok())
+          .map(|state| rng = GameRng::from_state(state)),
+        "static" => args.first().map(|w| static_bricks = *w == "1"),
+        "apple" => (|| {
+          let [food, pos] = args else { return None };
+          apple = Some(get_food_at(parse_food(food)?, parse_pos(pos)?));
+          Some(())
+        })(),
+        "bricks" => args.iter()
+          .map(|w| parse_pos(w).map(|pos| get_food_at(FoodType::Brick, pos)))
+          .collect::<Option<Vec<_>>>()
+          .map(|parsed| bricks = parsed),
+        "hazard" => (|| {
+          let [kind, pos, step, wait, path] = args else { return None };
+          hazards.push(Hazard::restore(
+            parse_hazard(kind)?, parse_pos(pos)?, parse_path(path)?,
+            step.parse().ok()?, wait.parse().ok()?
+          ));
+          Some(())
+        })(),
+        "portal" => (|| {
+          let [a, b] = args else { return None };
+          let index = portals.len();
+          portals.push(Portal::new(parse_pos(a)?, parse_pos(b)?, index));
+          Some(())
+        })(),
+        "player" => field_size
+          .and_then(|size| parse_player(args, size, players.len()))
+          .map(|player| players.push(player)),
+        _ => return Err(SnakeError::Save(
+          i + 1, tr!("save.unknown_record", keyword)
+        ))
+      };
+
+      if parsed.is_none() {
+        return Err(SnakeError::Save(
+          i + 1, tr!("save.bad_record", keyword)
+        ));
+      }
+    }
+
+    let missing = |what: &str| SnakeError::Save(0, tr!("save.missing_record", what));
+    let field_size = field_size.ok_or_else(|| missing("field"))?;
+    let apple = apple.ok_or_else(|| missing("apple"))?;
+    if players.is_empty() {
+      return Err(missing("player"));
+    }
+
+    let field = Field { apple, bricks, hazards, portals };
+    let outside = std::iter::once(field.apple.get_pos())
+      .chain(field.bricks.iter().map(|brick| brick.get_pos()))
+      .chain(field.hazards.iter().map(|hazard| hazard.get_pos()))
+      .chain(field.portals.iter().flat_map(|portal| portal.get_ends()))
+      .find(|pos| !field_size.contains(pos));
+    if let Some(pos) = outside {
+      return Err(SnakeError::Save(
+        0, tr!("save.outside_field", pos.x, pos.y)
+      ));
+    }
+
+    Ok(World {
+      field_size,
+      field,
+      players,
+      tick,
+      seed,
+      rng,
+      static_bricks
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::ai::Difficulty;
+  use crate::ui::dimensions::Size;
+  use crate::world::{World, GameOptions};
+  use crate::snake::Direction;
+
+  #[test]
+  fn test_encode_decode() {
+    let options = GameOptions {
+      arcade: true,
+      bots: vec![Difficulty::Greedy],
+      seed: Some(7),
+      ..GameOptions::default()
+    };
+    let mut world = World::new(Size::from((30, 20)), &options);
+    for _ in 0..40 {
+      world.tick();
+    }
+    world.players[0].push_direction(Direction::Up);
+    world.players[0].toggle_boost();
+
+    let source = world.encode();
+    let mut restored = World::decode(&source).unwrap();
+    assert_eq!(restored.encode(), source);
+    assert!(restored.players[1].bot == Some(Difficulty::Greedy));
+
+    for _ in 0..200 {
+      world.tick();
+      restored.tick();
+    }
+    assert_eq!(restored.encode(), world.encode());
+  }
+
+  #[test]
+  fn test_decode_errors() {
+    assert!(World::decode("field 10 10\nwall 1").is_err());
+    assert!(World::decode("field 10 10\napple g 3,3").is_err());
+    assert!(World::decode("field 10\n").is_err());
+
+    let player = "player - - 0 U - 0 0,0,0,0,0,-";
+    let save = |apple: &str, head: &str|
+      format!("field 10 10\napple g {apple}\n{player} {head}\n");
+    assert!(World::decode(&save("3,3", "2,1")).is_ok());
+    assert!(World::decode(&save("3,3", "2,0")).is_err());
+    assert!(World::decode(&save("3,3", "1,5")).is_err());
+    assert!(World::decode(&save("12,3", "2,1")).is_err());
+  }
+}